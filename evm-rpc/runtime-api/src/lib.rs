@@ -0,0 +1,52 @@
+//! Runtime API definition for querying and calling the EVM, and submitting Ethereum transactions.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+use codec::{Codec, Encode, Decode};
+use sp_core::{H160, U256};
+use sp_runtime::{RuntimeDebug, traits::Block as BlockT};
+
+pub use ethereum::Transaction;
+
+/// The EVM state of an address.
+#[derive(Eq, PartialEq, Clone, Default, Encode, Decode, RuntimeDebug)]
+pub struct EvmAccount {
+	/// The nonce of the address.
+	pub nonce: U256,
+	/// The EVM balance of the address.
+	pub balance: U256,
+}
+
+/// The outcome of executing a call against the EVM.
+#[derive(Eq, PartialEq, Clone, Encode, Decode, RuntimeDebug)]
+pub enum CallResult {
+	/// The call succeeded with the given output.
+	Succeed(Vec<u8>),
+	/// The call reverted with the given output.
+	Revert(Vec<u8>),
+	/// The call failed, for example by running out of gas.
+	Error,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the EVM state, execute calls against it and submit Ethereum transactions.
+	pub trait EVMApi<AccountId> where
+		AccountId: Codec,
+	{
+		/// Get the EVM address of the given account.
+		fn address_of(account: AccountId) -> H160;
+
+		/// Get the EVM nonce and balance of the given address.
+		fn account_basic(address: H160) -> EvmAccount;
+
+		/// Execute a call from `from` to `to` without committing any changes to the state.
+		fn call(from: H160, to: H160, data: Vec<u8>, value: U256, gas_limit: u32) -> CallResult;
+
+		/// Get the chain ID Ethereum transactions must be signed for, as defined by EIP-155.
+		fn chain_id() -> u64;
+
+		/// Wrap a signed Ethereum transaction into the extrinsic executing it.
+		fn transaction_extrinsic(transaction: Transaction) -> <Block as BlockT>::Extrinsic;
+	}
+}
@@ -0,0 +1,204 @@
+//! Ethereum-compatible RPC interface to the EVM.
+//!
+//! Besides reading the EVM state and executing calls against it, signed Ethereum transactions can
+//! be submitted to the transaction pool. Blocks are identified by their hash rather than by an
+//! Ethereum block tag, and default to the best block.
+
+use std::sync::Arc;
+use codec::Codec;
+use futures::{FutureExt, TryFutureExt};
+use serde::{Serialize, Deserialize};
+use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, H160, H256, U256, hexdisplay::HexDisplay, keccak_256};
+use jsonrpc_core::{BoxFuture, Error as RpcError, ErrorCode, Result, futures::future};
+use jsonrpc_derive::rpc;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use sp_api::ProvideRuntimeApi;
+use sp_transaction_pool::TransactionPool;
+pub use evm_rpc_runtime_api::{CallResult, EVMApi as EVMRuntimeApi, Transaction};
+
+/// The gas limit of calls that do not specify one.
+const DEFAULT_GAS_LIMIT: u32 = 10_000_000;
+
+/// A call to execute with `eth_call`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallRequest {
+	/// The sender, or the zero address if none is given.
+	pub from: Option<H160>,
+	/// The contract to call.
+	pub to: H160,
+	/// The gas limit of the call.
+	pub gas: Option<U256>,
+	/// The value transferred with the call.
+	pub value: Option<U256>,
+	/// The input data of the call.
+	pub data: Option<Bytes>,
+}
+
+/// Ethereum-compatible RPC methods.
+#[rpc]
+pub trait EthApi<BlockHash> {
+	/// Executes a call against the EVM without creating a transaction and returns its output.
+	#[rpc(name = "eth_call")]
+	fn call(&self, request: CallRequest, at: Option<BlockHash>) -> Result<Bytes>;
+
+	/// Returns the EVM balance of `address`.
+	#[rpc(name = "eth_getBalance")]
+	fn balance(&self, address: H160, at: Option<BlockHash>) -> Result<U256>;
+
+	/// Returns the nonce of `address`.
+	#[rpc(name = "eth_getTransactionCount")]
+	fn transaction_count(&self, address: H160, at: Option<BlockHash>) -> Result<U256>;
+
+	/// Returns the chain ID transactions must be signed for, as defined by EIP-155.
+	#[rpc(name = "eth_chainId")]
+	fn chain_id(&self) -> Result<U256>;
+
+	/// Submits an RLP-encoded signed transaction to the transaction pool and returns its hash.
+	#[rpc(name = "eth_sendRawTransaction")]
+	fn send_raw_transaction(&self, transaction: Bytes) -> BoxFuture<H256>;
+}
+
+/// An implementation of the Ethereum-compatible RPC methods.
+pub struct Eth<C, P, M, A> {
+	client: Arc<C>,
+	pool: Arc<P>,
+	_marker: std::marker::PhantomData<(M, A)>,
+}
+
+impl<C, P, M, A> Eth<C, P, M, A> {
+	/// Create new `Eth` with the given reference to the client and the transaction pool.
+	pub fn new(client: Arc<C>, pool: Arc<P>) -> Self {
+		Eth { client, pool, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+	/// The EVM call reverted.
+	Reverted,
+	/// The EVM call failed.
+	CallFailed,
+	/// The transaction could not be decoded.
+	InvalidTransaction,
+	/// The transaction pool rejected the transaction.
+	TransactionRejected,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::Reverted => 2,
+			Error::CallFailed => 3,
+			Error::InvalidTransaction => 4,
+			Error::TransactionRejected => 5,
+		}
+	}
+}
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: "Runtime trapped".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, P, Block, AccountId> EthApi<<Block as BlockT>::Hash> for Eth<C, P, Block, AccountId>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: EVMRuntimeApi<Block, AccountId>,
+	P: TransactionPool<Block = Block> + 'static,
+	AccountId: Codec + Send + Sync + 'static,
+{
+	fn call(&self, request: CallRequest, at: Option<<Block as BlockT>::Hash>) -> Result<Bytes> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		let CallRequest { from, to, gas, value, data } = request;
+		let gas_limit = gas.map(|gas| gas.min(U256::from(u32::max_value())).low_u32())
+			.unwrap_or(DEFAULT_GAS_LIMIT);
+
+		let result = api.call(
+			&at,
+			from.unwrap_or_default(),
+			to,
+			data.map(|data| data.0).unwrap_or_default(),
+			value.unwrap_or_default(),
+			gas_limit,
+		).map_err(runtime_error)?;
+
+		match result {
+			CallResult::Succeed(output) => Ok(output.into()),
+			CallResult::Revert(output) => Err(RpcError {
+				code: ErrorCode::ServerError(Error::Reverted.into()),
+				message: "Execution reverted".into(),
+				data: Some(format!("0x{}", HexDisplay::from(&output)).into()),
+			}),
+			CallResult::Error => Err(RpcError {
+				code: ErrorCode::ServerError(Error::CallFailed.into()),
+				message: "Execution failed".into(),
+				data: None,
+			}),
+		}
+	}
+
+	fn balance(&self, address: H160, at: Option<<Block as BlockT>::Hash>) -> Result<U256> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.account_basic(&at, address).map(|account| account.balance).map_err(runtime_error)
+	}
+
+	fn transaction_count(&self, address: H160, at: Option<<Block as BlockT>::Hash>) -> Result<U256> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.account_basic(&at, address).map(|account| account.nonce).map_err(runtime_error)
+	}
+	fn chain_id(&self) -> Result<U256> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(self.client.info().best_hash);
+
+		api.chain_id(&at).map(U256::from).map_err(runtime_error)
+	}
+
+	fn send_raw_transaction(&self, bytes: Bytes) -> BoxFuture<H256> {
+		let transaction = match rlp::decode::<Transaction>(&bytes) {
+			Ok(transaction) => transaction,
+			Err(e) => return Box::new(future::err(RpcError {
+				code: ErrorCode::ServerError(Error::InvalidTransaction.into()),
+				message: "Invalid transaction".into(),
+				data: Some(format!("{:?}", e).into()),
+			})),
+		};
+		// The hash of a transaction is the hash of its RLP encoding.
+		let hash = H256::from(keccak_256(&bytes));
+
+		let at = BlockId::hash(self.client.info().best_hash);
+		let extrinsic = match self.client.runtime_api().transaction_extrinsic(&at, transaction) {
+			Ok(extrinsic) => extrinsic,
+			Err(e) => return Box::new(future::err(runtime_error(e))),
+		};
+
+		Box::new(
+			self.pool.submit_one(&at, extrinsic)
+				.map_ok(move |_| hash)
+				.map_err(|e| RpcError {
+					code: ErrorCode::ServerError(Error::TransactionRejected.into()),
+					message: "Transaction rejected".into(),
+					data: Some(format!("{}", e).into()),
+				})
+				.boxed()
+				.compat()
+		)
+	}
+}
@@ -0,0 +1,149 @@
+//! The `benchmark` subcommand, running the runtime's benchmarks of a pallet's dispatchable.
+//!
+//! This is the command of `frame-benchmarking-cli`, which prints the samples as CSV followed by
+//! their analysis. With `--json`, the node runs that command again in a child process and prints
+//! the samples of its output as JSON instead.
+
+use std::{collections::BTreeMap, env, io, process::{Command, Stdio}};
+use serde::Serialize;
+use structopt::StructOpt;
+use sc_cli::VersionInfo;
+use sc_executor::NativeExecutionDispatch;
+use sc_service::{ChainSpec, Configuration};
+use node_template_runtime::opaque::Block;
+
+/// The last columns of the samples printed by `frame-benchmarking-cli`, after the components.
+const TIME_COLUMNS: &str = "extrinsic_time,storage_root_time";
+
+/// The `benchmark` command, benchmarking a pallet's dispatchable.
+#[derive(Debug, StructOpt)]
+pub struct BenchmarkCmd {
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub cmd: frame_benchmarking_cli::BenchmarkCmd,
+
+	/// Print the samples as JSON instead of printing them as CSV with their analysis.
+	#[structopt(long = "json")]
+	pub json: bool,
+}
+
+/// The samples of a benchmark, as printed with `--json`.
+#[derive(Serialize)]
+struct Report<'a> {
+	pallet: &'a str,
+	extrinsic: &'a str,
+	steps: &'a [u32],
+	repeat: u32,
+	samples: Vec<Sample>,
+}
+
+/// A single run of a benchmark. Times are in nanoseconds.
+#[derive(Serialize)]
+struct Sample {
+	components: BTreeMap<String, u32>,
+	extrinsic_time: u128,
+	storage_root_time: u128,
+}
+
+impl BenchmarkCmd {
+	/// Initialize the logger.
+	pub fn init(&self, version: &VersionInfo) -> sc_cli::Result<()> {
+		self.cmd.init(version)
+	}
+
+	/// Update the configuration with the chain spec given on the command line.
+	pub fn update_config<F>(
+		&self,
+		config: &mut Configuration,
+		spec_factory: F,
+		version: &VersionInfo,
+	) -> sc_cli::Result<()> where
+		F: FnOnce(&str) -> Result<Box<dyn ChainSpec>, String>,
+	{
+		self.cmd.update_config(config, spec_factory, version)
+	}
+
+	/// Run the benchmark against the genesis state of the chain and print its samples.
+	pub fn run<E>(self, config: Configuration) -> sc_cli::Result<()> where
+		E: NativeExecutionDispatch + 'static,
+	{
+		if !self.json {
+			return self.cmd.run::<Block, E>(config);
+		}
+
+		// `frame-benchmarking-cli` only prints its results, so they are read back from the
+		// output of the same command without `--json`.
+		let output = Command::new(env::current_exe()?)
+			.args(env::args_os().skip(1).filter(|arg| arg != "--json"))
+			.stderr(Stdio::inherit())
+			.output()?;
+		if !output.status.success() {
+			return Err(format!("The benchmark failed with {}", output.status).into());
+		}
+
+		let report = Report {
+			pallet: &self.cmd.pallet,
+			extrinsic: &self.cmd.extrinsic,
+			steps: &self.cmd.steps,
+			repeat: self.cmd.repeat,
+			samples: parse_samples(&String::from_utf8_lossy(&output.stdout))?,
+		};
+		serde_json::to_writer_pretty(io::stdout(), &report)
+			.map_err(|e| format!("Failed to write benchmark results: {}", e))?;
+		println!();
+
+		Ok(())
+	}
+}
+
+/// Read the samples of the CSV table printed by `frame-benchmarking-cli`: a header naming the
+/// components and then `TIME_COLUMNS`, followed by a row per sample up to an empty line.
+fn parse_samples(output: &str) -> Result<Vec<Sample>, String> {
+	let mut lines = output.lines().skip_while(|line| !line.ends_with(TIME_COLUMNS));
+	// The benchmark prints its errors instead of the table.
+	let header = lines.next().ok_or("The benchmark printed no samples")?;
+	let components = header.split(',').collect::<Vec<_>>();
+	let components = &components[..components.len() - 2];
+
+	lines.take_while(|line| !line.is_empty()).map(|line| {
+		let invalid = || format!("Invalid benchmark sample: {}", line);
+		let mut values = line.split(',');
+		let components = components.iter()
+			.map(|name| {
+				let value = values.next().and_then(|v| v.parse().ok()).ok_or_else(invalid)?;
+				Ok((name.to_string(), value))
+			})
+			.collect::<Result<_, String>>()?;
+		let mut time = || values.next().and_then(|v| v.parse().ok()).ok_or_else(invalid);
+		Ok(Sample { components, extrinsic_time: time()?, storage_root_time: time()? })
+	}).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn samples_are_read_from_the_csv_table() {
+		let output = "\
+Pallet: \"template\", Extrinsic: \"do_something\", Lowest values: [], Highest values: [], Steps: [1], Repeat: 2
+s,extrinsic_time,storage_root_time
+0,50000,20000
+0,48000,21000
+
+Median Slopes Analysis
+========
+";
+		let samples = parse_samples(output).unwrap();
+		assert_eq!(samples.len(), 2);
+		assert_eq!(samples[1].components.get("s"), Some(&0));
+		assert_eq!(samples[1].extrinsic_time, 48000);
+		assert_eq!(samples[1].storage_root_time, 21000);
+	}
+
+	#[test]
+	fn output_without_a_table_is_an_error() {
+		assert!(parse_samples("").is_err());
+		assert!(parse_samples("s,extrinsic_time,storage_root_time\n0,50000\n").is_err());
+	}
+}
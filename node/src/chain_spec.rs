@@ -1,18 +1,54 @@
 use sp_core::{Pair, Public, sr25519};
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, SystemConfig, WASM_BINARY, Signature
+	AccountId, AuraConfig, AuthorityDiscoveryConfig, Balance, BalancesConfig, BlockNumber,
+	ContractsConfig, ContractsSchedule, CouncilConfig, DemocracyConfig,
+	GenesisConfig, GrandpaConfig, IdentityRegistrarsConfig, ImOnlineConfig, IndicesConfig,
+	SessionConfig, StakingConfig, StakerStatus, SudoConfig, SystemConfig, TechnicalCommitteeConfig,
+	TechnicalMembershipConfig, TemplateModuleConfig, TreasuryConfig, ValidatorSetConfig,
+	VestingConfig, WASM_BINARY, Signature, DAYS, MILLICENTS, opaque::SessionKeys,
 };
 use sp_consensus_aura::sr25519::{AuthorityId as AuraId};
 use grandpa_primitives::{AuthorityId as GrandpaId};
+use pallet_im_online::sr25519::{AuthorityId as ImOnlineId};
+use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
 use sc_service;
-use sp_runtime::traits::{Verify, IdentifyAccount};
+use sc_chain_spec::ChainSpecExtension;
+use sp_runtime::{Perbill, traits::{Verify, IdentifyAccount}};
+use serde::{Serialize, Deserialize};
 
 // Note this is the URL for the telemetry server
 //const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
 
+/// GRANDPA voter settings that can be tuned per chain without recompiling the node.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrandpaSettings {
+	/// How often to gossip GRANDPA messages, in milliseconds.
+	pub gossip_duration_millis: u64,
+	/// The number of blocks between GRANDPA justifications.
+	pub justification_period: u32,
+}
+
+impl Default for GrandpaSettings {
+	fn default() -> Self {
+		GrandpaSettings {
+			gossip_duration_millis: 333,
+			justification_period: 512,
+		}
+	}
+}
+
+/// Node-specific chain spec extensions.
+#[derive(Default, Clone, Serialize, Deserialize, ChainSpecExtension)]
+#[serde(rename_all = "camelCase")]
+pub struct Extensions {
+	/// GRANDPA voter settings. Chain specs without them use the defaults.
+	#[serde(default)]
+	pub grandpa: GrandpaSettings,
+}
+
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
-pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
+pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig, Extensions>;
 
 /// The chain specification option. This is expected to come in from the CLI and
 /// is little more than one of a number of alternatives which can easily be converted
@@ -41,14 +77,34 @@ pub fn get_account_id_from_seed<TPublic: Public>(seed: &str) -> AccountId where
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
-/// Helper function to generate an authority key for Aura
-pub fn get_authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
+/// Helper function to generate the stash, controller and session keys of an authority from seed
+pub fn get_authority_keys_from_seed(s: &str) -> (
+	AccountId,
+	AccountId,
+	AuraId,
+	GrandpaId,
+	ImOnlineId,
+	AuthorityDiscoveryId,
+) {
 	(
+		get_account_id_from_seed::<sr25519::Public>(&format!("{}//stash", s)),
+		get_account_id_from_seed::<sr25519::Public>(s),
 		get_from_seed::<AuraId>(s),
 		get_from_seed::<GrandpaId>(s),
+		get_from_seed::<ImOnlineId>(s),
+		get_from_seed::<AuthorityDiscoveryId>(s),
 	)
 }
 
+fn session_keys(
+	aura: AuraId,
+	grandpa: GrandpaId,
+	im_online: ImOnlineId,
+	authority_discovery: AuthorityDiscoveryId,
+) -> SessionKeys {
+	SessionKeys { aura, grandpa, im_online, authority_discovery }
+}
+
 impl Alternative {
 	/// Get an actual chain config from one of the alternatives.
 	pub(crate) fn load(self) -> Result<ChainSpec, String> {
@@ -67,13 +123,16 @@ impl Alternative {
 						get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 						get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 					],
+					vec![
+						(get_account_id_from_seed::<sr25519::Public>("Bob"), 0, 1 * DAYS, ENDOWMENT / 2),
+					],
 					true,
 				),
 				vec![],
 				None,
 				None,
 				None,
-				None
+				Extensions::default(),
 			),
 			Alternative::LocalTestnet => ChainSpec::from_genesis(
 				"Local Testnet",
@@ -98,13 +157,17 @@ impl Alternative {
 						get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 						get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 					],
+					vec![
+						(get_account_id_from_seed::<sr25519::Public>("Eve"), 0, 28 * DAYS, ENDOWMENT / 4),
+						(get_account_id_from_seed::<sr25519::Public>("Ferdie"), 7 * DAYS, 28 * DAYS, ENDOWMENT / 10),
+					],
 					true,
 				),
 				vec![],
 				None,
 				None,
 				None,
-				None
+				Extensions::default(),
 			),
 		})
 	}
@@ -118,27 +181,115 @@ impl Alternative {
 	}
 }
 
-fn testnet_genesis(initial_authorities: Vec<(AuraId, GrandpaId)>,
+/// The balance every endowed account starts with.
+const ENDOWMENT: Balance = 1 << 60;
+/// The balance every initial authority bonds from its stash account.
+const STASH: Balance = ENDOWMENT / 1000;
+
+fn testnet_genesis(initial_authorities: Vec<(
+		AccountId,
+		AccountId,
+		AuraId,
+		GrandpaId,
+		ImOnlineId,
+		AuthorityDiscoveryId,
+	)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
-	_enable_println: bool) -> GenesisConfig {
+	vesting: Vec<(AccountId, BlockNumber, BlockNumber, Balance)>,
+	enable_println: bool) -> GenesisConfig {
+	// The first half of the endowed accounts sit on the council and the technical committee.
+	let num_members = (endowed_accounts.len() + 1) / 2;
+	let members = endowed_accounts.iter().take(num_members).cloned().collect::<Vec<_>>();
+
 	GenesisConfig {
 		system: Some(SystemConfig {
 			code: WASM_BINARY.to_vec(),
 			changes_trie_config: Default::default(),
 		}),
+		// The endowed accounts claim the indices 0, 1, 2 and so on, in order.
+		indices: Some(IndicesConfig {
+			indices: endowed_accounts.iter().cloned().enumerate().map(|(i, k)| (i as u32, k)).collect(),
+		}),
 		balances: Some(BalancesConfig {
-			balances: endowed_accounts.iter().cloned().map(|k|(k, 1 << 60)).collect(),
+			balances: endowed_accounts.iter().cloned().map(|k|(k, ENDOWMENT)).collect(),
+		}),
+		contracts: Some(ContractsConfig {
+			current_schedule: ContractsSchedule {
+				// Whether contracts may print to the node's console with `ext_println`.
+				enable_println,
+				.. Default::default()
+			},
+			gas_price: 1 * MILLICENTS,
+		}),
+		// The endowment of each account vests linearly over `length` blocks from `begin` on,
+		// except for the `liquid` amount.
+		vesting: Some(VestingConfig {
+			vesting,
+		}),
+		// Creates the treasury account.
+		treasury: Some(TreasuryConfig::default()),
+		staking: Some(StakingConfig {
+			validator_count: initial_authorities.len() as u32 * 2,
+			minimum_validator_count: initial_authorities.len() as u32,
+			stakers: initial_authorities.iter().map(|x| {
+				(x.0.clone(), x.1.clone(), STASH, StakerStatus::Validator)
+			}).collect(),
+			invulnerables: initial_authorities.iter().map(|x| x.0.clone()).collect(),
+			slash_reward_fraction: Perbill::from_percent(10),
+			.. Default::default()
+		}),
+		// Root has not changed the validators staking elects yet.
+		validator_set: Some(ValidatorSetConfig {
+			added: vec![],
+		}),
+		session: Some(SessionConfig {
+			keys: initial_authorities.iter().map(|x| {
+				(x.0.clone(), session_keys(x.2.clone(), x.3.clone(), x.4.clone(), x.5.clone()))
+			}).collect::<Vec<_>>(),
 		}),
+		// The initial authorities of these pallets are set by the session pallet.
 		aura: Some(AuraConfig {
-			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
+			authorities: vec![],
 		}),
 		grandpa: Some(GrandpaConfig {
-			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect(),
+			authorities: vec![],
+		}),
+		im_online: Some(ImOnlineConfig {
+			keys: vec![],
+		}),
+		authority_discovery: Some(AuthorityDiscoveryConfig {
+			keys: vec![],
+		}),
+		democracy: Some(DemocracyConfig::default()),
+		// The council sits until the first election, at the end of the first term, replaces it.
+		collective_Instance1: Some(CouncilConfig {
+			members: members.clone(),
+			phantom: Default::default(),
+		}),
+		// The technical committee is made up of the members of `membership_Instance1`.
+		collective_Instance2: Some(TechnicalCommitteeConfig {
+			members: vec![],
+			phantom: Default::default(),
+		}),
+		membership_Instance1: Some(TechnicalMembershipConfig {
+			members,
+			phantom: Default::default(),
+		}),
+		// `Alice` and `Bob` are the identity registrars 0 and 1, judging identities for free.
+		identity_registrars: Some(IdentityRegistrarsConfig {
+			registrars: vec![
+				(get_account_id_from_seed::<sr25519::Public>("Alice"), 0),
+				(get_account_id_from_seed::<sr25519::Public>("Bob"), 0),
+			],
 		}),
 		sudo: Some(SudoConfig {
 			key: root_key,
 		}),
+		template: Some(TemplateModuleConfig {
+			initial_value: Some(0),
+			accounts: endowed_accounts.iter().cloned().map(|k| (k, None)).collect(),
+		}),
 	}
 }
 
@@ -1,5 +1,7 @@
-use sc_cli::{RunCmd, Subcommand};
+use std::str::FromStr;
+use sc_cli::RunCmd;
 use structopt::StructOpt;
+use crate::chain_spec::GrandpaSettings;
 
 #[derive(Debug, StructOpt)]
 pub struct Cli {
@@ -8,4 +10,100 @@ pub struct Cli {
 
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	#[structopt(flatten)]
+	pub grandpa: GrandpaParams,
+
+	/// Seal blocks with manual seal instead of running Aura and GRANDPA.
+	///
+	/// `instant` seals a block as soon as a transaction enters the pool, `manual` only seals
+	/// blocks on `engine_createBlock` RPC calls, and `interval=<ms>` additionally seals and
+	/// finalizes a block every `<ms>` milliseconds.
+	#[structopt(long = "sealing", value_name = "instant|manual|interval=<ms>")]
+	pub sealing: Option<Sealing>,
+}
+
+/// How blocks are sealed when not running Aura and GRANDPA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sealing {
+	/// Seal a block as soon as a transaction enters the pool.
+	Instant,
+	/// Seal a block only when requested over RPC.
+	Manual,
+	/// Seal a block every given number of milliseconds, as well as when requested over RPC.
+	Interval(u64),
+}
+
+impl FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		const INTERVAL_PREFIX: &str = "interval=";
+
+		match s {
+			"instant" => Ok(Sealing::Instant),
+			"manual" => Ok(Sealing::Manual),
+			_ if s.starts_with(INTERVAL_PREFIX) => {
+				let millis = &s[INTERVAL_PREFIX.len()..];
+				millis.parse::<u64>()
+					.ok()
+					.filter(|millis| *millis > 0)
+					.map(Sealing::Interval)
+					.ok_or_else(|| format!("Invalid sealing interval: {}", millis))
+			},
+			_ => Err(format!("Unknown sealing method: {}", s)),
+		}
+	}
+}
+
+/// Command line overrides of the chain spec's GRANDPA settings.
+#[derive(Debug, Clone, Default, StructOpt)]
+pub struct GrandpaParams {
+	/// How often to gossip GRANDPA messages, in milliseconds.
+	#[structopt(long = "grandpa-gossip-duration", value_name = "MILLISECONDS")]
+	pub gossip_duration_millis: Option<u64>,
+
+	/// The number of blocks between GRANDPA justifications.
+	#[structopt(long = "grandpa-justification-period", value_name = "BLOCKS")]
+	pub justification_period: Option<u32>,
+
+	/// Run the lightweight GRANDPA observer instead of the full voter.
+	///
+	/// Only has an effect on non-authority nodes, which then follow finality
+	/// without taking part in the vote gossip.
+	#[structopt(long = "grandpa-observer")]
+	pub observer: bool,
+}
+
+impl GrandpaParams {
+	/// Apply the overrides given on the command line to the chain spec's settings.
+	pub fn apply(&self, settings: GrandpaSettings) -> GrandpaSettings {
+		GrandpaSettings {
+			gossip_duration_millis: self.gossip_duration_millis
+				.unwrap_or(settings.gossip_duration_millis),
+			justification_period: self.justification_period
+				.unwrap_or(settings.justification_period),
+		}
+	}
+}
+
+#[derive(Debug, StructOpt)]
+pub enum Subcommand {
+	#[structopt(flatten)]
+	Base(sc_cli::Subcommand),
+
+	/// The custom benchmark subcommand benchmarking runtime pallets.
+	#[cfg(feature = "runtime-benchmarks")]
+	#[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
+	Benchmark(crate::benchmarking::BenchmarkCmd),
+
+	/// Write the state of a block to a snapshot file for `try-runtime`.
+	#[cfg(feature = "try-runtime")]
+	#[structopt(name = "export-state", about = "Export the state of a block to a snapshot file.")]
+	ExportState(crate::try_runtime::ExportStateCmd),
+
+	/// Dry-run the runtime upgrade of a candidate runtime against a state snapshot.
+	#[cfg(feature = "try-runtime")]
+	#[structopt(name = "try-runtime", about = "Try a runtime upgrade against a state snapshot.")]
+	TryRuntime(crate::try_runtime::TryRuntimeCmd),
 }
@@ -6,6 +6,11 @@ mod chain_spec;
 mod service;
 mod cli;
 mod command;
+mod rpc;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(feature = "try-runtime")]
+mod try_runtime;
 
 fn main() -> sc_cli::Result<()> {
 	let version = sc_cli::VersionInfo {
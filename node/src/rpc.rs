@@ -0,0 +1,133 @@
+//! A collection of node-specific RPC methods.
+//!
+//! Substrate provides the `sc-rpc` crate, which defines the core RPC layer
+//! used by Substrate nodes. This file extends those RPC definitions with
+//! capabilities that are specific to this project's runtime configuration.
+
+use std::sync::Arc;
+
+use node_template_runtime::{
+	opaque::Block, AccountId, Balance, BlockNumber, Hash, Index, UncheckedExtrinsic,
+};
+use sc_consensus_manual_seal::{EngineCommand, rpc::{ManualSeal, ManualSealApi}};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::{Error as BlockChainError, HeaderMetadata, HeaderBackend};
+use sp_transaction_pool::TransactionPool;
+use sc_client_api::light::{Fetcher, RemoteBlockchain};
+
+/// The EVM runtime API, required of the runtime only when the node is built with the `evm`
+/// feature.
+#[cfg(feature = "evm")]
+pub trait EvmRuntimeApi: evm_rpc::EVMRuntimeApi<Block, AccountId> {}
+#[cfg(feature = "evm")]
+impl<T: evm_rpc::EVMRuntimeApi<Block, AccountId>> EvmRuntimeApi for T {}
+
+/// The EVM runtime API, required of the runtime only when the node is built with the `evm`
+/// feature.
+#[cfg(not(feature = "evm"))]
+pub trait EvmRuntimeApi {}
+#[cfg(not(feature = "evm"))]
+impl<T> EvmRuntimeApi for T {}
+
+/// Full client dependencies.
+pub struct FullDeps<C, P> {
+	/// The client instance to use.
+	pub client: Arc<C>,
+	/// Transaction pool instance.
+	pub pool: Arc<P>,
+	/// Channel to the manual seal authorship task, if blocks are sealed with manual seal.
+	pub command_sink: Option<futures::channel::mpsc::Sender<EngineCommand<Hash>>>,
+}
+
+/// Light client extra dependencies.
+pub struct LightDeps<C, F, P> {
+	/// The client instance to use.
+	pub client: Arc<C>,
+	/// Transaction pool instance.
+	pub pool: Arc<P>,
+	/// Remote access to the blockchain (async).
+	pub remote_blockchain: Arc<dyn RemoteBlockchain<Block>>,
+	/// Fetcher instance.
+	pub fetcher: Arc<F>,
+}
+
+/// Instantiate all full RPC extensions.
+pub fn create_full<C, P, M>(
+	deps: FullDeps<C, P>,
+) -> jsonrpc_core::IoHandler<M> where
+	C: ProvideRuntimeApi<Block>,
+	C: HeaderBackend<Block> + HeaderMetadata<Block, Error=BlockChainError> + 'static,
+	C: Send + Sync + 'static,
+	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
+	C::Api: pallet_contracts_rpc::ContractsRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance, UncheckedExtrinsic>,
+	C::Api: template_rpc::TemplateRuntimeApi<Block, AccountId>,
+	C::Api: EvmRuntimeApi,
+	P: TransactionPool<Block = Block> + 'static,
+	M: jsonrpc_core::Metadata + Default,
+{
+	use substrate_frame_rpc_system::{FullSystem, SystemApi};
+	use pallet_contracts_rpc::{Contracts, ContractsApi};
+	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
+	use template_rpc::{Template, TemplateApi};
+
+	let mut io = jsonrpc_core::IoHandler::default();
+	let FullDeps {
+		client,
+		pool,
+		command_sink,
+	} = deps;
+
+	io.extend_with(
+		SystemApi::to_delegate(FullSystem::new(client.clone(), pool.clone()))
+	);
+	io.extend_with(
+		ContractsApi::to_delegate(Contracts::new(client.clone()))
+	);
+	io.extend_with(
+		TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone()))
+	);
+	io.extend_with(
+		TemplateApi::to_delegate(Template::new(client.clone()))
+	);
+	#[cfg(feature = "evm")]
+	io.extend_with(
+		evm_rpc::EthApi::to_delegate(evm_rpc::Eth::new(client.clone(), pool))
+	);
+
+	if let Some(command_sink) = command_sink {
+		io.extend_with(
+			// We provide the rpc handler with the sending end of the channel to allow the rpc
+			// send EngineCommands to the background block authorship task.
+			ManualSealApi::to_delegate(ManualSeal::new(command_sink)),
+		);
+	}
+
+	io
+}
+
+/// Instantiate all light RPC extensions.
+pub fn create_light<C, P, M, F>(
+	deps: LightDeps<C, F, P>,
+) -> jsonrpc_core::IoHandler<M> where
+	C: HeaderBackend<Block>,
+	C: Send + Sync + 'static,
+	F: Fetcher<Block> + 'static,
+	P: TransactionPool + 'static,
+	M: jsonrpc_core::Metadata + Default,
+{
+	use substrate_frame_rpc_system::{LightSystem, SystemApi};
+
+	let LightDeps {
+		client,
+		pool,
+		remote_blockchain,
+		fetcher,
+	} = deps;
+	let mut io = jsonrpc_core::IoHandler::default();
+	io.extend_with(
+		SystemApi::<AccountId, Index>::to_delegate(LightSystem::new(client, remote_blockchain, fetcher, pool))
+	);
+
+	io
+}
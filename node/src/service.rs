@@ -1,17 +1,27 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
 use std::sync::Arc;
-use std::time::Duration;
+use futures::prelude::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use sc_client::LongestChain;
 use sc_client_api::ExecutorProvider;
-use node_template_runtime::{self, opaque::Block, RuntimeApi};
+use node_template_runtime::{self, opaque::Block, RuntimeApi, SLOT_DURATION};
 use sc_service::{error::{Error as ServiceError}, AbstractService, Configuration, ServiceBuilder};
-use sp_inherents::InherentDataProviders;
+use sc_network::Event;
+use sc_consensus_manual_seal::{self as manual_seal, EngineCommand};
+use sp_inherents::{InherentData, InherentDataProviders, InherentIdentifier, ProvideInherentData};
+use sp_timestamp::{InherentError as TimestampInherentError, INHERENT_IDENTIFIER as TIMESTAMP_INHERENT_IDENTIFIER};
+use crate::chain_spec::GrandpaSettings;
+use crate::cli::{GrandpaParams, Sealing};
 use sc_executor::native_executor_instance;
 pub use sc_executor::NativeExecutor;
 use sp_consensus_aura::sr25519::{AuthorityPair as AuraPair};
 use grandpa::{self, FinalityProofProvider as GrandpaFinalityProofProvider, StorageAndProofProvider};
 
+/// The RPC extensions served by the node in addition to the stock Substrate RPCs.
+pub type RpcExtension = jsonrpc_core::IoHandler<sc_rpc::Metadata>;
+
 // Our native executor instance.
 native_executor_instance!(
 	pub Executor,
@@ -62,6 +72,15 @@ macro_rules! new_full_start {
 				import_setup = Some((grandpa_block_import, grandpa_link));
 
 				Ok(import_queue)
+			})?
+			.with_rpc_extensions(|builder| -> Result<crate::service::RpcExtension, _> {
+				let deps = crate::rpc::FullDeps {
+					client: builder.client().clone(),
+					pool: builder.pool(),
+					command_sink: None,
+				};
+
+				Ok(crate::rpc::create_full(deps))
 			})?;
 
 		(builder, import_setup, inherent_data_providers)
@@ -69,13 +88,21 @@ macro_rules! new_full_start {
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration)
+pub fn new_full(config: Configuration, grandpa_params: GrandpaParams)
 	-> Result<impl AbstractService, ServiceError>
 {
+	let grandpa_settings = grandpa_params.apply(
+		config.chain_spec.as_ref()
+			.and_then(|spec| sc_chain_spec::get_extension::<GrandpaSettings>(spec.extensions()))
+			.cloned()
+			.unwrap_or_default()
+	);
+
 	let is_authority = config.roles.is_authority();
 	let force_authoring = config.force_authoring;
 	let name = config.name.clone();
 	let disable_grandpa = config.disable_grandpa;
+	let sentry_nodes = config.network.sentry_nodes.clone();
 
 	// sentry nodes announce themselves as authorities to the network
 	// and should run the same protocols authorities do, but it should
@@ -125,6 +152,24 @@ pub fn new_full(config: Configuration)
 		// the AURA authoring task is considered essential, i.e. if it
 		// fails we take down the service with it.
 		service.spawn_essential_task("aura", aura);
+
+		// publish our addresses to, and look up those of, the other
+		// authorities through the DHT.
+		let network = service.network();
+		let dht_event_stream = network.event_stream().filter_map(|e| async move { match e {
+			Event::Dht(e) => Some(e),
+			_ => None,
+		}}).boxed();
+		let authority_discovery = sc_authority_discovery::AuthorityDiscovery::new(
+			service.client(),
+			network,
+			sentry_nodes,
+			service.keystore(),
+			dht_event_stream,
+			service.prometheus_registry(),
+		);
+
+		service.spawn_task("authority-discovery", authority_discovery);
 	}
 
 	// if the node isn't actively participating in consensus then it doesn't
@@ -135,23 +180,36 @@ pub fn new_full(config: Configuration)
 		None
 	};
 
+	let enable_grandpa = !disable_grandpa;
+	let run_observer = enable_grandpa && !is_authority && grandpa_params.observer;
+
 	let grandpa_config = grandpa::Config {
-		// FIXME #1578 make this available through chainspec
-		gossip_duration: Duration::from_millis(333),
-		justification_period: 512,
+		gossip_duration: Duration::from_millis(grandpa_settings.gossip_duration_millis),
+		justification_period: grandpa_settings.justification_period,
 		name: Some(name),
-		observer_enabled: false,
+		// only the observer uses this, the full voter always disables it.
+		observer_enabled: run_observer,
 		keystore,
 		is_authority,
 	};
 
-	let enable_grandpa = !disable_grandpa;
-	if enable_grandpa {
+	if run_observer {
+		// start the lightweight GRANDPA observer, which follows finality by
+		// listening to commit messages instead of taking part in voting.
+		service.spawn_task(
+			"grandpa-observer",
+			grandpa::run_grandpa_observer(grandpa_config, grandpa_link, service.network())?,
+		);
+	} else if enable_grandpa {
+		if grandpa_params.observer {
+			log::warn!("Authorities always run the full GRANDPA voter, ignoring --grandpa-observer");
+		}
+
 		// start the full GRANDPA voter
-		// NOTE: non-authorities could run the GRANDPA observer protocol, but at
-		// this point the full voter should provide better guarantees of block
-		// and vote data availability than the observer. The observer has not
-		// been tested extensively yet and having most nodes in a network run it
+		// NOTE: non-authorities can opt into running the GRANDPA observer protocol
+		// instead with `--grandpa-observer`, but by default the full voter is used
+		// since it provides better guarantees of block and vote data availability
+		// than the observer. Having most nodes in a network run the observer
 		// could lead to finality stalls.
 		let grandpa_config = grandpa::GrandpaParams {
 			config: grandpa_config,
@@ -180,6 +238,142 @@ pub fn new_full(config: Configuration)
 	Ok(service)
 }
 
+/// Provides the timestamp inherent for blocks sealed with manual seal.
+///
+/// Blocks may be sealed much faster than one per slot, which the timestamp and Aura pallets
+/// would reject, so instead of the wall clock every block gets a timestamp exactly one
+/// `SLOT_DURATION` after the previous one, starting from the time the node was started.
+pub struct MockTimestampInherentDataProvider {
+	next: AtomicU64,
+}
+
+impl Default for MockTimestampInherentDataProvider {
+	fn default() -> Self {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH)
+			.expect("Current time is always after unix epoch; qed")
+			.as_millis() as u64;
+
+		MockTimestampInherentDataProvider { next: AtomicU64::new(now) }
+	}
+}
+
+impl ProvideInherentData for MockTimestampInherentDataProvider {
+	fn inherent_identifier(&self) -> &'static InherentIdentifier {
+		&TIMESTAMP_INHERENT_IDENTIFIER
+	}
+
+	fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), sp_inherents::Error> {
+		let timestamp = self.next.fetch_add(SLOT_DURATION, Ordering::SeqCst);
+		inherent_data.put_data(TIMESTAMP_INHERENT_IDENTIFIER, &timestamp)
+	}
+
+	fn error_to_string(&self, error: &[u8]) -> Option<String> {
+		TimestampInherentError::try_from(&TIMESTAMP_INHERENT_IDENTIFIER, error).map(|e| format!("{:?}", e))
+	}
+}
+
+/// Builds a new service for a full client which seals blocks with manual seal instead of
+/// running Aura and GRANDPA.
+pub fn new_manual_seal(config: Configuration, sealing: Sealing)
+	-> Result<impl AbstractService, ServiceError>
+{
+	let inherent_data_providers = InherentDataProviders::new();
+	inherent_data_providers
+		.register_provider(MockTimestampInherentDataProvider::default())
+		.map_err(sp_consensus::Error::InherentData)?;
+
+	// RPC calls to `engine_createBlock` and `engine_finalizeBlock` are sent to the authorship
+	// task through this channel.
+	let (command_sink, commands_stream) = futures::channel::mpsc::channel(1000);
+
+	let service = ServiceBuilder::new_full::<Block, RuntimeApi, Executor>(config)?
+		.with_select_chain(|_config, backend| {
+			Ok(LongestChain::new(backend.clone()))
+		})?
+		.with_transaction_pool(|config, client, _fetcher| {
+			let pool_api = sc_transaction_pool::FullChainApi::new(client.clone());
+			Ok(sc_transaction_pool::BasicPool::new(config, Arc::new(pool_api)))
+		})?
+		.with_import_queue(|_config, client, _select_chain, _transaction_pool| {
+			Ok(manual_seal::import_queue(Box::new(client)))
+		})?
+		.with_rpc_extensions(|builder| -> Result<RpcExtension, _> {
+			let deps = crate::rpc::FullDeps {
+				client: builder.client().clone(),
+				pool: builder.pool(),
+				command_sink: Some(command_sink.clone()),
+			};
+
+			Ok(crate::rpc::create_full(deps))
+		})?
+		.build()?;
+
+	let proposer = sc_basic_authorship::ProposerFactory::new(
+		service.client(),
+		service.transaction_pool(),
+	);
+
+	let client = service.client();
+	let select_chain = service.select_chain()
+		.ok_or(ServiceError::SelectChainRequired)?;
+	let pool = service.transaction_pool().pool().clone();
+
+	match sealing {
+		Sealing::Instant => {
+			let authorship_future = manual_seal::run_instant_seal(
+				Box::new(client.clone()),
+				proposer,
+				client,
+				pool,
+				select_chain,
+				inherent_data_providers,
+			);
+
+			service.spawn_essential_task("instant-seal", authorship_future);
+		},
+		Sealing::Manual => {
+			let authorship_future = manual_seal::run_manual_seal(
+				Box::new(client.clone()),
+				proposer,
+				client,
+				pool,
+				commands_stream,
+				select_chain,
+				inherent_data_providers,
+			);
+
+			service.spawn_essential_task("manual-seal", authorship_future);
+		},
+		Sealing::Interval(millis) => {
+			let interval = Duration::from_millis(millis);
+			let ticks = futures::stream::unfold((), move |()| async move {
+				futures_timer::Delay::new(interval).await;
+				let command = EngineCommand::SealNewBlock {
+					create_empty: true,
+					finalize: true,
+					parent_hash: None,
+					sender: None,
+				};
+				Some((command, ()))
+			});
+
+			let authorship_future = manual_seal::run_manual_seal(
+				Box::new(client.clone()),
+				proposer,
+				client,
+				pool,
+				futures::stream::select(commands_stream, ticks),
+				select_chain,
+				inherent_data_providers,
+			);
+
+			service.spawn_essential_task("interval-seal", authorship_future);
+		},
+	}
+
+	Ok(service)
+}
+
 /// Builds a new service for a light client.
 pub fn new_light(config: Configuration)
 	-> Result<impl AbstractService, ServiceError>
@@ -230,5 +424,20 @@ pub fn new_light(config: Configuration)
 			let provider = client as Arc<dyn StorageAndProofProvider<_, _>>;
 			Ok(Arc::new(GrandpaFinalityProofProvider::new(backend, provider)) as _)
 		})?
+		.with_rpc_extensions(|builder| -> Result<RpcExtension, _> {
+			let fetcher = builder.fetcher()
+				.ok_or_else(|| "Trying to start node RPC without active fetcher")?;
+			let remote_blockchain = builder.remote_backend()
+				.ok_or_else(|| "Trying to start node RPC without active remote blockchain")?;
+
+			let deps = crate::rpc::LightDeps {
+				client: builder.client().clone(),
+				pool: builder.pool(),
+				remote_blockchain,
+				fetcher,
+			};
+
+			Ok(crate::rpc::create_light(deps))
+		})?
 		.build()
 }
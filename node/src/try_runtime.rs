@@ -0,0 +1,276 @@
+//! Dry runs of runtime upgrades against a snapshot of the chain's state.
+//!
+//! `export-state` writes the state of a block to a snapshot file, and `try-runtime` runs the
+//! migrations of a candidate runtime against that snapshot, without a database or network.
+
+use std::{collections::BTreeMap, fs::{self, File}, iter, path::PathBuf, str::FromStr, sync::Arc};
+use log::{info, warn};
+use serde::{Serialize, Deserialize};
+use structopt::StructOpt;
+use sc_cli::{SharedParams, VersionInfo, WasmExecutionMethod};
+use sc_client_api::{Backend, StorageProvider};
+use sc_executor::{NativeExecutionDispatch, NativeExecutor};
+use sc_service::{ChainSpec, Configuration};
+use sp_blockchain::HeaderBackend;
+use sp_core::{hexdisplay::HexDisplay, tasks};
+use sp_core::storage::{ChildInfo, Storage, StorageChild, StorageData, StorageKey, well_known_keys};
+use sp_runtime::{generic::BlockId, traits::BlakeTwo256};
+use sp_state_machine::{
+	Backend as _, ExecutionStrategy, InMemoryBackend, OverlayedChanges, StateMachine,
+};
+use sp_state_machine::backend::BackendRuntimeCode;
+use codec::Decode;
+use node_template_runtime::{opaque::Block, BlockNumber, Hash, MaximumBlockWeight, Weight};
+
+/// The prefix of the keys of default child tries in the main trie.
+const DEFAULT_CHILD_STORAGE_KEY_PREFIX: &[u8] = b":child_storage:default:";
+
+/// The state of a block, as written by `export-state`.
+#[derive(Serialize, Deserialize)]
+pub struct Snapshot {
+	/// The hash of the block the state is of.
+	pub at: Hash,
+	/// The storage of the main trie.
+	pub top: BTreeMap<StorageKey, StorageData>,
+	/// The storage of the child tries, such as the storage of contracts, by their key in the main
+	/// trie.
+	#[serde(default)]
+	pub children: BTreeMap<StorageKey, BTreeMap<StorageKey, StorageData>>,
+}
+
+/// The info of the child trie under `key` of the main trie.
+///
+/// Only default child tries whose unique id is their key without the prefix, as the contracts
+/// pallet creates them, are supported. The storage of other child tries cannot be read.
+fn child_info(key: &StorageKey) -> Result<ChildInfo, String> {
+	if !well_known_keys::is_child_trie_key_valid(&key.0) {
+		return Err(format!("Unsupported child trie 0x{}", HexDisplay::from(&key.0)));
+	}
+	Ok(ChildInfo::new_default(&key.0[DEFAULT_CHILD_STORAGE_KEY_PREFIX.len()..]))
+}
+
+/// The `export-state` command, writing the state of a block to a snapshot file.
+#[derive(Debug, StructOpt)]
+pub struct ExportStateCmd {
+	/// The hash of the block to export the state of. Defaults to the best block.
+	#[structopt(long = "at", value_name = "HASH")]
+	pub at: Option<String>,
+
+	/// The snapshot file to write.
+	#[structopt(long = "output", value_name = "FILE", parse(from_os_str))]
+	pub output: PathBuf,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl ExportStateCmd {
+	/// Initialize the logger.
+	pub fn init(&self, version: &VersionInfo) -> sc_cli::Result<()> {
+		self.shared_params.init(version)
+	}
+
+	/// Update the configuration with the chain spec and database given on the command line.
+	pub fn update_config<F>(
+		&self,
+		config: &mut Configuration,
+		spec_factory: F,
+		version: &VersionInfo,
+	) -> sc_cli::Result<()> where
+		F: FnOnce(&str) -> Result<Box<dyn ChainSpec>, String>,
+	{
+		self.shared_params.update_config(config, spec_factory, version)?;
+		config.use_in_memory_keystore()?;
+		Ok(())
+	}
+
+	/// Write the state of the block to the snapshot file.
+	pub fn run<B, C>(&self, client: Arc<C>) -> sc_cli::Result<()> where
+		B: Backend<Block>,
+		C: StorageProvider<Block, B> + HeaderBackend<Block>,
+	{
+		let at = match &self.at {
+			Some(at) => Hash::from_str(at.trim_start_matches("0x"))
+				.map_err(|e| format!("Invalid block hash {}: {:?}", at, e))?,
+			None => client.info().best_hash,
+		};
+
+		let block = BlockId::Hash(at);
+		let top = client.storage_pairs(&block, &StorageKey(Vec::new()))
+			.map_err(|e| format!("Error reading the state of block {}: {:?}", at, e))?
+			.into_iter()
+			.collect::<BTreeMap<_, _>>();
+		let children = top.iter()
+			.filter(|(key, _)| well_known_keys::is_child_storage_key(&key.0))
+			.map(|(key, root)| {
+				Ok((key.clone(), export_child_trie::<B, C>(&*client, &block, key, root)?))
+			})
+			.collect::<Result<BTreeMap<_, _>, String>>()?;
+		let keys = top.len() + children.values().map(BTreeMap::len).sum::<usize>();
+
+		let file = File::create(&self.output)?;
+		serde_json::to_writer(file, &Snapshot { at, top, children })
+			.map_err(|e| format!("Error writing the snapshot: {}", e))?;
+
+		info!("Exported {} keys of the state of block {} to {}", keys, at, self.output.display());
+		Ok(())
+	}
+}
+
+/// Read the child trie under `key` of the main trie, checking that it has the given `root`.
+///
+/// The export fails rather than writing a snapshot with an incomplete child trie.
+fn export_child_trie<B, C>(
+	client: &C,
+	block: &BlockId<Block>,
+	key: &StorageKey,
+	root: &StorageData,
+) -> Result<BTreeMap<StorageKey, StorageData>, String> where
+	B: Backend<Block>,
+	C: StorageProvider<Block, B>,
+{
+	let child_info = child_info(key)?;
+	let read_error = |e: sp_blockchain::Error| {
+		format!("Error reading the child trie 0x{}: {:?}", HexDisplay::from(&key.0), e)
+	};
+	let data = client.child_storage_keys(block, key, child_info, &StorageKey(Vec::new()))
+		.map_err(read_error)?
+		.into_iter()
+		.filter_map(|child_key| {
+			client.child_storage(block, key, child_info, &child_key)
+				.map_err(read_error)
+				.map(|value| value.map(|value| (child_key, value)))
+				.transpose()
+		})
+		.collect::<Result<BTreeMap<_, _>, String>>()?;
+
+	let state = InMemoryBackend::<BlakeTwo256>::from(Storage {
+		top: Default::default(),
+		children: iter::once((key.0.clone(), StorageChild {
+			data: data.iter().map(|(key, value)| (key.0.clone(), value.0.clone())).collect(),
+			child_info: child_info.to_owned(),
+		})).collect(),
+	});
+	let (exported_root, _, _) = state.child_storage_root(&key.0, child_info, iter::empty());
+	if exported_root.as_bytes() != &root.0[..] {
+		return Err(format!(
+			"The child trie 0x{} could not be read in full, refusing to write an incomplete snapshot",
+			HexDisplay::from(&key.0),
+		));
+	}
+	Ok(data)
+}
+
+/// The `try-runtime` command, running the migrations of a candidate runtime against a snapshot.
+#[derive(Debug, StructOpt)]
+pub struct TryRuntimeCmd {
+	/// The snapshot file written by `export-state`.
+	#[structopt(long = "snapshot", value_name = "FILE", parse(from_os_str))]
+	pub snapshot: PathBuf,
+
+	/// The Wasm blob of the candidate runtime, built with the `try-runtime` feature.
+	#[structopt(long = "runtime", value_name = "FILE", parse(from_os_str))]
+	pub runtime: PathBuf,
+
+	/// Method for executing the candidate runtime.
+	#[structopt(
+		long = "wasm-execution",
+		value_name = "METHOD",
+		possible_values = &WasmExecutionMethod::enabled_variants(),
+		case_insensitive = true,
+		default_value = "Interpreted"
+	)]
+	pub wasm_method: WasmExecutionMethod,
+
+	/// The number of 64KB pages to allocate for Wasm execution.
+	#[structopt(long = "heap-pages", value_name = "COUNT")]
+	pub heap_pages: Option<u64>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl TryRuntimeCmd {
+	/// Initialize the logger.
+	pub fn init(&self, version: &VersionInfo) -> sc_cli::Result<()> {
+		self.shared_params.init(version)
+	}
+
+	/// Run the migrations of the candidate runtime against the snapshot, and report the weight
+	/// they consumed.
+	pub fn run<ExecDispatch>(&self) -> sc_cli::Result<()> where
+		ExecDispatch: NativeExecutionDispatch + 'static,
+	{
+		let snapshot: Snapshot = serde_json::from_reader(File::open(&self.snapshot)?)
+			.map_err(|e| format!("Error reading the snapshot: {}", e))?;
+		let code = fs::read(&self.runtime)?;
+		info!("Trying the runtime upgrade on the state of block {}", snapshot.at);
+
+		// Snapshots written before child tries were exported lack them, so the migrations would
+		// see empty contract storage.
+		if let Some(key) = snapshot.top.keys().find(|key| {
+			well_known_keys::is_child_storage_key(&key.0) && !snapshot.children.contains_key(key)
+		}) {
+			return Err(format!(
+				"The snapshot lacks the child trie 0x{}, export the state again",
+				HexDisplay::from(&key.0),
+			).into());
+		}
+
+		let children = snapshot.children.into_iter()
+			.map(|(key, data)| {
+				let child_info = child_info(&key)?.to_owned();
+				let data = data.into_iter().map(|(key, value)| (key.0, value.0)).collect();
+				Ok((key.0, StorageChild { data, child_info }))
+			})
+			.collect::<Result<_, String>>()?;
+		// The candidate runtime replaces the one of the snapshot, as `set_code` would.
+		let mut top = snapshot.top.into_iter()
+			.map(|(key, value)| (key.0, value.0))
+			.collect::<BTreeMap<_, _>>();
+		top.insert(well_known_keys::CODE.to_vec(), code);
+		let state = InMemoryBackend::<BlakeTwo256>::from(Storage { top, children });
+
+		let executor = NativeExecutor::<ExecDispatch>::new(
+			self.wasm_method.into(),
+			self.heap_pages,
+			// The runtime instances cache size.
+			2,
+		);
+		let runtime_code = BackendRuntimeCode::new(&state).runtime_code()
+			.map_err(|e| format!("Error loading the candidate runtime: {}", e))?;
+		let mut changes = OverlayedChanges::default();
+		let result = StateMachine::<_, _, BlockNumber, _>::new(
+			&state,
+			None,
+			&mut changes,
+			&executor,
+			"TryRuntime_on_runtime_upgrade",
+			&[],
+			Default::default(),
+			&runtime_code,
+			tasks::executor(),
+		)
+			// The native runtime is the node's own, not the candidate.
+			.execute(ExecutionStrategy::AlwaysWasm)
+			.map_err(|e| format!("Error executing the runtime upgrade: {:?}", e))?;
+
+		let weight = <Result<Weight, String>>::decode(&mut &result[..])
+			.map_err(|e| format!("Error decoding the result of the runtime upgrade: {:?}", e))?
+			.map_err(|e| format!("Runtime upgrade check failed: {}", e))?;
+
+		let max_weight = MaximumBlockWeight::get();
+		info!(
+			"Runtime upgrade succeeded, consuming a weight of {} ({}% of the maximum block weight {})",
+			weight,
+			weight.saturating_mul(100) / max_weight,
+			max_weight,
+		);
+		if weight >= max_weight {
+			warn!("The weight reported is capped to the maximum block weight, and may be higher");
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,128 @@
+//! Checks that a non-authority node running the GRANDPA observer still learns
+//! about finality from the voters it is connected to, without running a voter itself.
+
+use assert_cmd::cargo::cargo_bin;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::net::TcpListener;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+/// Peer id derived from the node key `0000...0001`.
+const ALICE_PEER_ID: &str = "QmRpheLN4JWdAnY7HGJfWFNbfkQCb6tFf4vvA6hgjMZKrR";
+
+/// How long to wait for the observer to see a finalized block.
+const TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Debug lines of the `afg` target that only a node running the GRANDPA voter logs.
+const VOTER_LINES: &[&str] = &["Starting new voter", "Casting prevote", "prevote equivocation"];
+
+/// The hex encoded key type of GRANDPA keys, which prefixes the names of their keystore files.
+const GRANDPA_KEY_TYPE: &str = "6772616e";
+
+/// Kills the wrapped node when dropped, so failing assertions don't leak processes.
+struct KillOnDrop(Child);
+
+impl Drop for KillOnDrop {
+	fn drop(&mut self) {
+		let _ = self.0.kill();
+		let _ = self.0.wait();
+	}
+}
+
+/// Returns a port that was free a moment ago, so that the test doesn't clash with running nodes.
+fn free_port() -> String {
+	TcpListener::bind("127.0.0.1:0")
+		.and_then(|listener| listener.local_addr())
+		.expect("could not find a free port")
+		.port()
+		.to_string()
+}
+
+/// Extracts the finalized block number from an informant log line.
+fn finalized_number(line: &str) -> Option<u64> {
+	let start = line.find("finalized #")? + "finalized #".len();
+	let digits: String = line[start..].chars().take_while(|c| c.is_ascii_digit()).collect();
+	digits.parse().ok()
+}
+
+/// Whether the keystore under `base_path` holds any GRANDPA key.
+fn has_grandpa_key(base_path: &Path) -> bool {
+	fs::read_dir(base_path.join("chains").join("dev").join("keystore"))
+		.map(|keys| keys.filter_map(Result::ok).any(|key| {
+			key.file_name().to_string_lossy().starts_with(GRANDPA_KEY_TYPE)
+		}))
+		.unwrap_or(false)
+}
+
+#[test]
+fn grandpa_observer_learns_finality_without_voting() {
+	let alice_port = free_port();
+	let alice_dir = tempdir().expect("could not create a temp dir");
+	let observer_dir = tempdir().expect("could not create a temp dir");
+
+	let _alice = KillOnDrop(Command::new(cargo_bin("node-template"))
+		.args(&["--dev", "--base-path"])
+		.arg(alice_dir.path())
+		.args(&[
+			"--port", &alice_port,
+			"--rpc-port", &free_port(),
+			"--ws-port", &free_port(),
+			"--node-key", "0000000000000000000000000000000000000000000000000000000000000001",
+		])
+		.stdout(Stdio::null())
+		.stderr(Stdio::null())
+		.spawn()
+		.expect("could not start the authority node"));
+
+	let mut observer = KillOnDrop(Command::new(cargo_bin("node-template"))
+		.args(&["--chain", "dev", "--base-path"])
+		.arg(observer_dir.path())
+		.args(&[
+			"--port", &free_port(),
+			"--rpc-port", &free_port(),
+			"--ws-port", &free_port(),
+			"--bootnodes", &format!("/ip4/127.0.0.1/tcp/{}/p2p/{}", alice_port, ALICE_PEER_ID),
+			"--grandpa-observer",
+			"--log", "afg=debug",
+		])
+		.stdout(Stdio::null())
+		.stderr(Stdio::piped())
+		.spawn()
+		.expect("could not start the observer node"));
+
+	let stderr = observer.0.stderr.take().expect("stderr is piped; qed");
+	let (tx, rx) = mpsc::channel();
+	thread::spawn(move || {
+		for line in BufReader::new(stderr).lines() {
+			match line {
+				Ok(line) => if tx.send(line).is_err() {
+					break;
+				},
+				Err(_) => break,
+			}
+		}
+	});
+
+	let deadline = Instant::now() + TIMEOUT;
+	loop {
+		let remaining = deadline.saturating_duration_since(Instant::now());
+		let line = rx.recv_timeout(remaining).unwrap_or_else(|_| {
+			panic!("the observer did not see any finalized block within {:?}", TIMEOUT)
+		});
+		assert!(
+			!VOTER_LINES.iter().any(|voter_line| line.contains(voter_line)),
+			"the observer runs a GRANDPA voter: {}",
+			line,
+		);
+		if finalized_number(&line).map_or(false, |number| number > 0) {
+			break;
+		}
+	}
+
+	assert!(!has_grandpa_key(observer_dir.path()), "the observer has a GRANDPA key");
+}
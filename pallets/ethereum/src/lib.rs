@@ -0,0 +1,256 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// A FRAME pallet executing Ethereum transactions against the EVM pallet.
+///
+/// A transaction signed with an Ethereum key is submitted as an unsigned `transact` extrinsic.
+/// Its signature takes the place of the extrinsic's: the sender is the EVM address recovered from
+/// it, which pays the gas fee from its EVM balance and whose EVM nonce the transaction must have.
+/// Transactions must be signed for `ChainId`, as defined by EIP-155, so that they cannot be
+/// replayed on other chains.
+
+use sp_std::prelude::*;
+use codec::Encode;
+use frame_support::{decl_module, decl_event, decl_error, ensure};
+use frame_support::dispatch::DispatchResult;
+use frame_support::traits::Get;
+use frame_support::weights::{DispatchClass, FunctionOf, Weight};
+use sp_core::{H160, H256, U256};
+use sp_runtime::transaction_validity::{
+	InvalidTransaction, TransactionLongevity, TransactionPriority, TransactionValidity,
+	ValidTransaction,
+};
+use evm::{Config, ExitReason, backend::ApplyBackend, executor::StackExecutor};
+use pallet_evm::{Backend, FeeCalculator, Vicinity};
+use system::ensure_none;
+
+pub use ethereum::{Transaction, TransactionAction, TransactionSignature};
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+/// The EVM configuration transactions are executed with.
+static ISTANBUL_CONFIG: Config = Config::istanbul();
+
+/// The error code of transactions whose gas limit does not cover their intrinsic gas.
+const GAS_LIMIT_TOO_LOW: u8 = 0;
+
+/// The pallet's configuration trait.
+pub trait Trait: pallet_evm::Trait {
+	/// The overarching event type.
+	type Event: From<Event> + Into<<Self as system::Trait>::Event>;
+
+	/// The chain ID transactions must be signed for.
+	type ChainId: Get<u64>;
+}
+
+decl_event!(
+	pub enum Event {
+		/// An Ethereum transaction was executed, whether or not it succeeded.
+		/// [sender, transaction hash, succeeded]
+		Executed(H160, H256, bool),
+		/// An Ethereum transaction created a contract. [address]
+		Created(H160),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// The transaction's signature is invalid or not for this chain
+		InvalidSignature,
+		/// The transaction's nonce is not the sender's
+		InvalidNonce,
+		/// The transaction's gas limit does not cover its intrinsic gas
+		GasLimitTooLow,
+		/// The transaction's gas limit does not fit in a block
+		GasLimitTooHigh,
+		/// The transaction's gas price is below the minimum gas price
+		GasPriceTooLow,
+		/// The sender cannot pay the transaction's value and gas fee
+		BalanceLow,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Execute a signed Ethereum transaction.
+		///
+		/// The extrinsic must be unsigned. Its weight is the transaction's gas limit.
+		#[weight = FunctionOf(
+			|args: (&Transaction,)| gas_limit(args.0).unwrap_or(u32::max_value()) as Weight,
+			DispatchClass::Normal,
+			true
+		)]
+		pub fn transact(origin, transaction: Transaction) -> DispatchResult {
+			ensure_none(origin)?;
+
+			let source = Self::check(&transaction)?;
+			ensure!(
+				transaction.nonce == pallet_evm::Module::<T>::accounts(source).nonce,
+				Error::<T>::InvalidNonce
+			);
+
+			Self::execute(source, transaction);
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> From<Error<T>> for InvalidTransaction {
+	fn from(e: Error<T>) -> Self {
+		match e {
+			Error::<T>::GasLimitTooLow => InvalidTransaction::Custom(GAS_LIMIT_TOO_LOW),
+			Error::<T>::GasLimitTooHigh => InvalidTransaction::ExhaustsResources,
+			Error::<T>::GasPriceTooLow | Error::<T>::BalanceLow => InvalidTransaction::Payment,
+			_ => InvalidTransaction::BadProof,
+		}
+	}
+}
+
+/// The gas limit of `transaction`, if it fits in a `u32` as the EVM pallet requires.
+fn gas_limit(transaction: &Transaction) -> Option<u32> {
+	if transaction.gas_limit > U256::from(u32::max_value()) {
+		None
+	} else {
+		Some(transaction.gas_limit.low_u32())
+	}
+}
+
+/// The gas `transaction` costs before any code runs, as of the Istanbul hard fork.
+///
+/// Executing a transaction whose gas limit is below its intrinsic gas would fail before
+/// incrementing the sender's nonce, so such transactions are invalid.
+fn intrinsic_gas(transaction: &Transaction) -> u64 {
+	let base = match transaction.action {
+		TransactionAction::Call(_) => 21_000,
+		TransactionAction::Create => 53_000,
+	};
+	transaction.input.iter()
+		.map(|byte| if *byte == 0 { 4 } else { 16 })
+		.fold(base, |gas, byte_gas| gas.saturating_add(byte_gas))
+}
+
+impl<T: Trait> Module<T> {
+	/// The hash of `transaction`, as Ethereum identifies it.
+	pub fn transaction_hash(transaction: &Transaction) -> H256 {
+		H256::from(sp_io::hashing::keccak_256(&rlp::encode(transaction)))
+	}
+
+	/// The EVM address whose key signed `transaction` for this chain, if its signature is valid.
+	pub fn sender(transaction: &Transaction) -> Option<H160> {
+		let chain_id = Some(T::ChainId::get());
+		if transaction.signature.chain_id() != chain_id {
+			return None;
+		}
+
+		let mut signature = [0u8; 65];
+		signature[0..32].copy_from_slice(&transaction.signature.r()[..]);
+		signature[32..64].copy_from_slice(&transaction.signature.s()[..]);
+		signature[64] = transaction.signature.standard_v();
+		let message = transaction.message_hash(chain_id);
+
+		let public = sp_io::crypto::secp256k1_ecdsa_recover(&signature, message.as_fixed_bytes())
+			.ok()?;
+		Some(H160::from(H256::from(sp_io::hashing::keccak_256(&public))))
+	}
+
+	/// Check everything about `transaction` but its nonce, returning its sender.
+	fn check(transaction: &Transaction) -> Result<H160, Error<T>> {
+		let source = Self::sender(transaction).ok_or(Error::<T>::InvalidSignature)?;
+		let gas_limit = gas_limit(transaction).ok_or(Error::<T>::GasLimitTooHigh)?;
+		ensure!(gas_limit as u64 >= intrinsic_gas(transaction), Error::<T>::GasLimitTooLow);
+		ensure!(
+			transaction.gas_price >= T::FeeCalculator::min_gas_price(),
+			Error::<T>::GasPriceTooLow
+		);
+
+		let fee = transaction.gas_price.checked_mul(U256::from(gas_limit))
+			.ok_or(Error::<T>::BalanceLow)?;
+		let payment = fee.checked_add(transaction.value).ok_or(Error::<T>::BalanceLow)?;
+		ensure!(
+			pallet_evm::Module::<T>::accounts(source).balance >= payment,
+			Error::<T>::BalanceLow
+		);
+
+		Ok(source)
+	}
+
+	/// Execute a checked `transaction` from `source`, committing its changes whether or not it
+	/// succeeds, as Ethereum does. The unused part of the gas fee is refunded.
+	fn execute(source: H160, transaction: Transaction) {
+		let hash = Self::transaction_hash(&transaction);
+		let Transaction { gas_price, gas_limit, action, value, input, .. } = transaction;
+		let gas_limit = gas_limit.low_u32() as usize;
+		let fee = gas_price.saturating_mul(U256::from(gas_limit));
+
+		let vicinity = Vicinity { gas_price, origin: source };
+		let mut backend = Backend::<T>::new(&vicinity);
+		let mut executor = StackExecutor::new(&backend, gas_limit, &ISTANBUL_CONFIG);
+
+		// The balance was checked, so withdrawing the fee cannot fail.
+		let _ = executor.withdraw(source, fee);
+		let (reason, created) = match action {
+			TransactionAction::Call(target) => {
+				(executor.transact_call(source, target, value, input, gas_limit).0, None)
+			},
+			TransactionAction::Create => {
+				let address = executor.create_address(
+					evm::CreateScheme::Legacy { caller: source },
+				);
+				(executor.transact_create(source, value, input, gas_limit), Some(address))
+			},
+		};
+		let succeeded = match reason {
+			ExitReason::Succeed(_) => true,
+			ExitReason::Revert(_) | ExitReason::Error(_) | ExitReason::Fatal(_) => false,
+		};
+
+		let used_fee = executor.fee(gas_price);
+		executor.deposit(source, fee.saturating_sub(used_fee));
+		let (values, logs) = executor.deconstruct();
+		backend.apply(values, logs, true);
+
+		Self::deposit_event(Event::Executed(source, hash, succeeded));
+		if let (true, Some(address)) = (succeeded, created) {
+			Self::deposit_event(Event::Created(address));
+		}
+	}
+}
+
+impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+
+	fn validate_unsigned(call: &Self::Call) -> TransactionValidity {
+		let transaction = match call {
+			Call::transact(transaction) => transaction,
+			_ => return InvalidTransaction::Call.into(),
+		};
+
+		let source = Self::check(transaction).map_err(InvalidTransaction::from)?;
+		let nonce = pallet_evm::Module::<T>::accounts(source).nonce;
+		if transaction.nonce < nonce {
+			return InvalidTransaction::Stale.into();
+		}
+
+		// Transactions with later nonces wait in the pool for the ones before them.
+		let requires = if transaction.nonce > nonce {
+			vec![(source, transaction.nonce - U256::one()).encode()]
+		} else {
+			vec![]
+		};
+		Ok(ValidTransaction {
+			priority: transaction.gas_price.min(U256::from(TransactionPriority::max_value()))
+				.low_u64(),
+			requires,
+			provides: vec![(source, transaction.nonce).encode()],
+			longevity: TransactionLongevity::max_value(),
+			propagate: true,
+		})
+	}
+}
@@ -0,0 +1,161 @@
+// Creating mock runtime here
+
+use crate::{Module, Trait, Transaction, TransactionAction, TransactionSignature};
+use sp_core::{H160, H256, U256};
+use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use pallet_evm::{ConvertAccountId, FeeCalculator};
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = system {}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1_000_000_000;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+impl system::Trait for Test {
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl balances::Trait for Test {
+	type Balance = u64;
+	type Event = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+}
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1;
+}
+impl timestamp::Trait for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+}
+
+/// A minimum gas price of one unit per gas.
+pub struct FixedGasPrice;
+impl FeeCalculator for FixedGasPrice {
+	fn min_gas_price() -> U256 {
+		U256::one()
+	}
+}
+
+/// The secret key of an account, which also signs its Ethereum transactions.
+pub fn secret_key(account: u64) -> secp256k1::SecretKey {
+	let mut seed = [0u8; 32];
+	seed[24..].copy_from_slice(&account.to_be_bytes());
+	secp256k1::SecretKey::parse(&seed).expect("the seed is a valid key")
+}
+
+/// The EVM address of an account is the Ethereum address of its key, so that the transactions
+/// signed with it spend the EVM balance deposited from the account.
+pub struct KeyAddress;
+impl ConvertAccountId<u64> for KeyAddress {
+	fn convert_account_id(account: &u64) -> H160 {
+		let public = secp256k1::PublicKey::from_secret_key(&secret_key(*account)).serialize();
+		H160::from(H256::from(sp_io::hashing::keccak_256(&public[1..])))
+	}
+}
+
+impl pallet_evm::Trait for Test {
+	type FeeCalculator = FixedGasPrice;
+	type ConvertAccountId = KeyAddress;
+	type Currency = Balances;
+	type Event = ();
+	type Precompiles = ();
+}
+parameter_types! {
+	pub const ChainId: u64 = 42;
+}
+impl Trait for Test {
+	type Event = ();
+	type ChainId = ChainId;
+}
+pub type System = system::Module<Test>;
+pub type Balances = balances::Module<Test>;
+pub type EVM = pallet_evm::Module<Test>;
+pub type Ethereum = Module<Test>;
+
+/// The EVM address of `account`.
+pub fn address(account: u64) -> H160 {
+	KeyAddress::convert_account_id(&account)
+}
+
+/// A transaction with the given nonce, action and input, and a gas price of one, signed by no one.
+pub fn transaction(nonce: u64, action: TransactionAction, input: Vec<u8>) -> Transaction {
+	let one = H256::from_low_u64_be(1);
+	Transaction {
+		nonce: U256::from(nonce),
+		gas_price: U256::one(),
+		gas_limit: U256::from(1_000_000),
+		action,
+		value: U256::zero(),
+		input,
+		signature: TransactionSignature::new(27, one, one).expect("the signature is valid"),
+	}
+}
+
+/// Sign `transaction` with the key of `account` for the chain `chain_id`, following EIP-155 if
+/// there is one.
+pub fn sign_for(mut transaction: Transaction, account: u64, chain_id: Option<u64>) -> Transaction {
+	let hash = transaction.message_hash(chain_id);
+	let (signature, recovery_id) = secp256k1::sign(
+		&secp256k1::Message::parse(hash.as_fixed_bytes()),
+		&secret_key(account),
+	);
+	let signature = signature.serialize();
+	let v = recovery_id.serialize() as u64 + chain_id.map_or(27, |id| id * 2 + 35);
+	transaction.signature = TransactionSignature::new(
+		v,
+		H256::from_slice(&signature[0..32]),
+		H256::from_slice(&signature[32..64]),
+	).expect("the signature is valid");
+	transaction
+}
+
+/// Sign `transaction` with the key of `account` for this chain.
+pub fn sign(transaction: Transaction, account: u64) -> Transaction {
+	sign_for(transaction, account, Some(ChainId::get()))
+}
+
+// Builds a genesis storage in which accounts 1 and 2 own 1 << 40 units, and 1 deposited half of
+// them to its EVM address.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	balances::GenesisConfig::<Test> {
+		balances: vec![(1, 1 << 40), (2, 1 << 40)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext = sp_io::TestExternalities::from(t);
+	ext.execute_with(|| {
+		EVM::deposit_balance(Origin::signed(1), 1 << 39).unwrap();
+	});
+	ext
+}
@@ -0,0 +1,138 @@
+// Tests for the Ethereum pallet
+
+use crate::{Error, TransactionAction, mock::*};
+use frame_support::{assert_ok, assert_noop};
+use frame_support::unsigned::ValidateUnsigned;
+use codec::Encode;
+use sp_core::{H160, H256, U256};
+use sp_runtime::DispatchError;
+use sp_runtime::transaction_validity::InvalidTransaction;
+
+/// The init code of the runtime's `SimpleStorage` contract, and the selector of its `set(uint256)`.
+const SIMPLE_STORAGE: &str = include_str!("../../../runtime/res/SimpleStorage.bin");
+const SET: [u8; 4] = [0x60, 0xfe, 0x47, 0xb1];
+
+fn transfer(nonce: u64, to: H160, value: u64) -> crate::Transaction {
+	let mut unsigned = transaction(nonce, TransactionAction::Call(to), vec![]);
+	unsigned.value = U256::from(value);
+	sign(unsigned, 1)
+}
+
+fn validate(transaction: crate::Transaction) -> Result<Vec<Vec<u8>>, InvalidTransaction> {
+	Ethereum::validate_unsigned(&crate::Call::transact(transaction))
+		.map(|valid| valid.requires)
+		.map_err(|e| match e {
+			sp_runtime::transaction_validity::TransactionValidityError::Invalid(e) => e,
+			_ => panic!("the transaction's validity is known"),
+		})
+}
+
+#[test]
+fn signers_are_recovered() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Ethereum::sender(&transfer(0, address(2), 0)), Some(address(1)));
+
+		// signatures are only valid for this chain
+		let unsigned = transaction(0, TransactionAction::Call(address(2)), vec![]);
+		assert_eq!(Ethereum::sender(&sign_for(unsigned.clone(), 1, Some(1))), None);
+		assert_eq!(Ethereum::sender(&sign_for(unsigned, 1, None)), None);
+	});
+}
+
+#[test]
+fn transactions_transfer_value_and_pay_for_gas() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Ethereum::transact(Origin::NONE, transfer(0, address(2), 1_000)));
+
+		let sender = EVM::accounts(address(1));
+		assert_eq!(sender.nonce, U256::one());
+		// a plain transfer uses 21 000 gas, at a gas price of one
+		assert_eq!(sender.balance, U256::from((1u64 << 39) - 1_000 - 21_000));
+		assert_eq!(EVM::accounts(address(2)).balance, U256::from(1_000));
+	});
+}
+
+#[test]
+fn transactions_create_and_call_contracts() {
+	new_test_ext().execute_with(|| {
+		let init = hex::decode(SIMPLE_STORAGE.trim()).expect("the fixture is hex");
+		assert_ok!(Ethereum::transact(
+			Origin::NONE,
+			sign(transaction(0, TransactionAction::Create, init), 1),
+		));
+		assert_eq!(EVM::accounts(address(1)).nonce, U256::one());
+
+		// the contract has the address Ethereum clients expect
+		let mut stream = rlp::RlpStream::new_list(2);
+		stream.append(&address(1));
+		stream.append(&U256::zero());
+		let contract = H160::from(H256::from(sp_io::hashing::keccak_256(&stream.out())));
+		assert!(!EVM::account_codes(contract).is_empty());
+
+		let mut set = SET.to_vec();
+		set.extend(H256::from_low_u64_be(42).as_bytes());
+		assert_ok!(Ethereum::transact(
+			Origin::NONE,
+			sign(transaction(1, TransactionAction::Call(contract), set), 1),
+		));
+		assert_eq!(EVM::account_storages(contract, H256::zero()), H256::from_low_u64_be(42));
+	});
+}
+
+#[test]
+fn only_unsigned_extrinsics_can_transact() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Ethereum::transact(Origin::signed(1), transfer(0, address(2), 1_000)),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn transactions_need_the_senders_nonce() {
+	new_test_ext().execute_with(|| {
+		// later transactions are valid, waiting for the ones before them, but cannot be executed
+		assert_eq!(
+			validate(transfer(1, address(2), 0)),
+			Ok(vec![(address(1), U256::zero()).encode()]),
+		);
+		assert_noop!(
+			Ethereum::transact(Origin::NONE, transfer(1, address(2), 0)),
+			Error::<Test>::InvalidNonce
+		);
+
+		assert_eq!(validate(transfer(0, address(2), 0)), Ok(vec![]));
+		assert_ok!(Ethereum::transact(Origin::NONE, transfer(0, address(2), 0)));
+		assert_eq!(validate(transfer(0, address(2), 0)), Err(InvalidTransaction::Stale));
+		assert_noop!(
+			Ethereum::transact(Origin::NONE, transfer(0, address(2), 0)),
+			Error::<Test>::InvalidNonce
+		);
+	});
+}
+
+#[test]
+fn invalid_transactions_are_rejected() {
+	new_test_ext().execute_with(|| {
+		let call = |gas_limit: u64, gas_price: u64, value: u64, signer: u64| {
+			let mut unsigned = transaction(0, TransactionAction::Call(address(2)), vec![]);
+			unsigned.gas_limit = U256::from(gas_limit);
+			unsigned.gas_price = U256::from(gas_price);
+			unsigned.value = U256::from(value);
+			sign(unsigned, signer)
+		};
+
+		assert_eq!(validate(call(20_999, 1, 0, 1)), Err(InvalidTransaction::Custom(0)));
+		assert_eq!(validate(call(1 << 32, 1, 0, 1)), Err(InvalidTransaction::ExhaustsResources));
+		assert_eq!(validate(call(21_000, 0, 0, 1)), Err(InvalidTransaction::Payment));
+		assert_eq!(validate(call(21_000, 1, 1 << 39, 1)), Err(InvalidTransaction::Payment));
+		// account 2 has no EVM balance
+		assert_eq!(validate(call(21_000, 1, 0, 2)), Err(InvalidTransaction::Payment));
+
+		assert_noop!(
+			Ethereum::transact(Origin::NONE, call(21_000, 1, 0, 2)),
+			Error::<Test>::BalanceLow
+		);
+	});
+}
@@ -0,0 +1,50 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// A FRAME pallet registering the registrars of the identity pallet at genesis.
+///
+/// The identity pallet only lets root add registrars through an extrinsic. This pallet adds the
+/// registrars listed in its genesis config in order, so the first one gets index 0, and sets
+/// their fees. Listing an account twice is rejected, as it would be given two indices.
+
+use sp_std::prelude::*;
+use frame_support::{decl_module, decl_storage, dispatch::Dispatchable, traits::Currency};
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+type BalanceOf<T> =
+	<<T as identity::Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// The pallet's configuration trait.
+pub trait Trait: identity::Trait {}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as IdentityRegistrars {}
+	add_extra_genesis {
+		/// The registrars to add, with the fee each of them charges per judgement.
+		config(registrars): Vec<(T::AccountId, BalanceOf<T>)>;
+		build(|config: &GenesisConfig<T>| {
+			let first = <identity::Module<T>>::registrars().len() as u32;
+			for (index, (account, fee)) in config.registrars.iter().enumerate() {
+				assert!(
+					config.registrars[..index].iter().all(|(other, _)| other != account),
+					"a genesis registrar is listed twice",
+				);
+				// The identity pallet's dispatchables are only reachable through its calls.
+				identity::Call::<T>::add_registrar(account.clone())
+					.dispatch(system::RawOrigin::Root.into())
+					.expect("the identity pallet accepts registrars from root; qed");
+				identity::Call::<T>::set_fee(first + index as u32, *fee)
+					.dispatch(system::RawOrigin::Signed(account.clone()).into())
+					.expect("the registrar was just added; qed");
+			}
+		});
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
+}
@@ -0,0 +1,42 @@
+// Tests for the identity registrars pallet
+
+use crate::mock::*;
+use frame_support::{assert_ok, dispatch::Dispatchable};
+use identity::RegistrarInfo;
+
+#[test]
+fn genesis_registrars_are_added_in_order() {
+	new_test_ext(vec![(1, 5), (2, 0)]).execute_with(|| {
+		assert_eq!(Identity::registrars(), vec![
+			Some(RegistrarInfo { account: 1, fee: 5, fields: Default::default() }),
+			Some(RegistrarInfo { account: 2, fee: 0, fields: Default::default() }),
+		]);
+	});
+}
+
+#[test]
+fn genesis_registrars_act_with_their_index() {
+	new_test_ext(vec![(1, 5), (2, 0)]).execute_with(|| {
+		// each registrar can change its own fee, and only its own
+		let set_fee = |index, fee| {
+			identity::Call::<Test>::set_fee(index, fee).dispatch(Origin::signed(2))
+		};
+		assert_ok!(set_fee(1, 7));
+		assert!(set_fee(0, 7).is_err());
+		assert_eq!(Identity::registrars()[0].as_ref().map(|r| r.fee), Some(5));
+		assert_eq!(Identity::registrars()[1].as_ref().map(|r| r.fee), Some(7));
+	});
+}
+
+#[test]
+#[should_panic(expected = "a genesis registrar is listed twice")]
+fn duplicate_genesis_registrars_are_rejected() {
+	new_test_ext(vec![(1, 5), (2, 0), (1, 3)]);
+}
+
+#[test]
+fn genesis_without_registrars_works() {
+	new_test_ext(vec![]).execute_with(|| {
+		assert!(Identity::registrars().is_empty());
+	});
+}
@@ -0,0 +1,212 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// A FRAME pallet letting accounts dispatch calls on behalf of other accounts.
+///
+/// An account registers other accounts as its proxies, each with a proxy type. A proxy can then
+/// dispatch calls as if they were signed by the account, as long as its proxy type lets the call
+/// through (see `InstanceFilter`). Every registered proxy reserves a deposit from the account,
+/// which is returned when the proxy is removed.
+
+use sp_std::prelude::*;
+use frame_support::{decl_module, decl_storage, decl_event, decl_error, ensure, Parameter};
+use frame_support::dispatch::DispatchResult;
+use frame_support::traits::{Currency, Get, ReservableCurrency};
+use frame_support::weights::{FunctionOf, GetDispatchInfo, SimpleDispatchInfo};
+use sp_runtime::traits::{Dispatchable, Member, Saturating, Zero};
+use system::ensure_signed;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+
+/// Decides whether a proxy of some type may dispatch a given call.
+pub trait InstanceFilter<T>: Sized + Send + Sync {
+	/// Whether the call `c` may be dispatched by a proxy of this type.
+	fn filter(&self, c: &T) -> bool;
+}
+
+/// A single proxy type that lets every call through.
+impl<T> InstanceFilter<T> for () {
+	fn filter(&self, _: &T) -> bool { true }
+}
+
+/// The pallet's configuration trait.
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The overarching call type, which proxies dispatch.
+	type Call: Parameter + Dispatchable<Origin=<Self as system::Trait>::Origin> + GetDispatchInfo;
+
+	/// The currency the proxy deposits are reserved in.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The kinds of proxies an account can have, and the calls each of them may dispatch.
+	type ProxyType: Parameter + Member + Ord + Default + InstanceFilter<<Self as Trait>::Call>;
+
+	/// The deposit reserved for having any proxies at all.
+	type ProxyDepositBase: Get<BalanceOf<Self>>;
+
+	/// The deposit reserved for every proxy.
+	type ProxyDepositFactor: Get<BalanceOf<Self>>;
+
+	/// The maximum number of proxies an account can have.
+	type MaxProxies: Get<u16>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Proxy {
+		/// The proxies of each account, ordered by proxy account and type, and the deposit
+		/// reserved for them.
+		pub Proxies get(fn proxies):
+			map hasher(twox_64_concat) T::AccountId
+			=> (Vec<(T::AccountId, T::ProxyType)>, BalanceOf<T>);
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		AccountId = <T as system::Trait>::AccountId,
+		ProxyType = <T as Trait>::ProxyType,
+	{
+		/// A proxy was added to an account. [who, proxy, proxy_type]
+		ProxyAdded(AccountId, AccountId, ProxyType),
+		/// A proxy was removed from an account. [who, proxy, proxy_type]
+		ProxyRemoved(AccountId, AccountId, ProxyType),
+		/// A call dispatched by a proxy was executed. [result]
+		ProxyExecuted(DispatchResult),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// The account already has the maximum number of proxies
+		TooMany,
+		/// The proxy is not registered for the account
+		NotFound,
+		/// The sender is not a proxy of the account
+		NotProxy,
+		/// None of the sender's proxy types lets the call through
+		Unproxyable,
+		/// The proxy is already registered for the account
+		Duplicate,
+		/// An account cannot be its own proxy
+		NoSelfProxy,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		const ProxyDepositBase: BalanceOf<T> = T::ProxyDepositBase::get();
+
+		const ProxyDepositFactor: BalanceOf<T> = T::ProxyDepositFactor::get();
+
+		const MaxProxies: u16 = T::MaxProxies::get();
+
+		fn deposit_event() = default;
+
+		/// Dispatch `call` as if it was signed by `real`.
+		///
+		/// The sender must be a proxy of `real` whose proxy type lets `call` through.
+		#[weight = FunctionOf(
+			|args: (&T::AccountId, &Box<<T as Trait>::Call>)| {
+				args.1.get_dispatch_info().weight.saturating_add(10_000)
+			},
+			|args: (&T::AccountId, &Box<<T as Trait>::Call>)| args.1.get_dispatch_info().class,
+			true
+		)]
+		pub fn proxy(origin, real: T::AccountId, call: Box<<T as Trait>::Call>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let (proxies, _) = Self::proxies(&real);
+			ensure!(proxies.iter().any(|(p, _)| p == &who), Error::<T>::NotProxy);
+			ensure!(
+				proxies.iter().any(|(p, t)| p == &who && t.filter(&call)),
+				Error::<T>::Unproxyable
+			);
+
+			let result = call.dispatch(system::RawOrigin::Signed(real).into());
+			Self::deposit_event(RawEvent::ProxyExecuted(result));
+			Ok(())
+		}
+
+		/// Register `proxy` as a proxy of the sender, of type `proxy_type`.
+		///
+		/// Reserves `ProxyDepositFactor` from the sender, and `ProxyDepositBase` for its first proxy.
+		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
+		pub fn add_proxy(origin, proxy: T::AccountId, proxy_type: T::ProxyType) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(proxy != who, Error::<T>::NoSelfProxy);
+
+			let (mut proxies, old_deposit) = Self::proxies(&who);
+			ensure!(proxies.len() < T::MaxProxies::get() as usize, Error::<T>::TooMany);
+			let entry = (proxy.clone(), proxy_type.clone());
+			let index = proxies.binary_search(&entry).err().ok_or(Error::<T>::Duplicate)?;
+			proxies.insert(index, entry);
+
+			let new_deposit = Self::deposit(proxies.len());
+			T::Currency::reserve(&who, new_deposit.saturating_sub(old_deposit))?;
+			<Proxies<T>>::insert(&who, (proxies, new_deposit));
+
+			Self::deposit_event(RawEvent::ProxyAdded(who, proxy, proxy_type));
+			Ok(())
+		}
+
+		/// Unregister `proxy` of type `proxy_type` from the sender's proxies.
+		///
+		/// Returns the part of the deposit that is no longer needed.
+		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
+		pub fn remove_proxy(origin, proxy: T::AccountId, proxy_type: T::ProxyType) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let (mut proxies, old_deposit) = Self::proxies(&who);
+			let entry = (proxy.clone(), proxy_type.clone());
+			let index = proxies.binary_search(&entry).ok().ok_or(Error::<T>::NotFound)?;
+			proxies.remove(index);
+
+			let new_deposit = if proxies.is_empty() {
+				<Proxies<T>>::remove(&who);
+				Zero::zero()
+			} else {
+				let new_deposit = Self::deposit(proxies.len());
+				<Proxies<T>>::insert(&who, (proxies, new_deposit));
+				new_deposit
+			};
+			T::Currency::unreserve(&who, old_deposit.saturating_sub(new_deposit));
+
+			Self::deposit_event(RawEvent::ProxyRemoved(who, proxy, proxy_type));
+			Ok(())
+		}
+
+		/// Unregister all of the sender's proxies and return the whole deposit.
+		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
+		pub fn remove_proxies(origin) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let (proxies, deposit) = <Proxies<T>>::take(&who);
+			T::Currency::unreserve(&who, deposit);
+
+			for (proxy, proxy_type) in proxies {
+				Self::deposit_event(RawEvent::ProxyRemoved(who.clone(), proxy, proxy_type));
+			}
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The deposit reserved for `count` proxies.
+	pub fn deposit(count: usize) -> BalanceOf<T> {
+		if count == 0 {
+			return Zero::zero();
+		}
+		T::ProxyDepositBase::get()
+			.saturating_add(T::ProxyDepositFactor::get().saturating_mul((count as u32).into()))
+	}
+}
@@ -0,0 +1,86 @@
+// Tests for the proxy pallet
+
+use crate::{Error, mock::*};
+use frame_support::{assert_ok, assert_noop};
+
+fn transfer(dest: u64, value: u64) -> Box<Call> {
+	Box::new(Call::Balances(balances::Call::transfer(dest, value)))
+}
+
+#[test]
+fn adding_and_removing_proxies_reserves_deposits() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any));
+		assert_eq!(Balances::reserved_balance(1), 3);
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::JustTransfer));
+		assert_eq!(Balances::reserved_balance(1), 5);
+		assert_eq!(Proxy::proxies(1).0, vec![(2, ProxyType::Any), (3, ProxyType::JustTransfer)]);
+
+		assert_ok!(Proxy::remove_proxy(Origin::signed(1), 2, ProxyType::Any));
+		assert_eq!(Balances::reserved_balance(1), 3);
+		assert_ok!(Proxy::remove_proxy(Origin::signed(1), 3, ProxyType::JustTransfer));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Proxy::proxies(1), (vec![], 0));
+	});
+}
+
+#[test]
+fn invalid_proxies_are_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Proxy::add_proxy(Origin::signed(1), 1, ProxyType::Any), Error::<Test>::NoSelfProxy);
+		assert_noop!(Proxy::remove_proxy(Origin::signed(1), 2, ProxyType::Any), Error::<Test>::NotFound);
+
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any));
+		assert_noop!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any), Error::<Test>::Duplicate);
+		// the same account may be a proxy of another type
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::JustTransfer));
+	});
+}
+
+#[test]
+fn proxies_are_limited() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any));
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::JustTransfer));
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Any));
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::JustTransfer));
+		assert_noop!(Proxy::add_proxy(Origin::signed(1), 4, ProxyType::Any), Error::<Test>::TooMany);
+	});
+}
+
+#[test]
+fn removing_all_proxies_returns_the_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 2, ProxyType::Any));
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Any));
+		assert_ok!(Proxy::remove_proxies(Origin::signed(1)));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Proxy::proxies(1), (vec![], 0));
+	});
+}
+
+#[test]
+fn proxies_dispatch_calls_their_type_allows() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::JustTransfer));
+		assert_ok!(Proxy::proxy(Origin::signed(3), 1, transfer(2, 2)));
+		assert_eq!(Balances::free_balance(1), 5);
+		assert_eq!(Balances::free_balance(2), 12);
+
+		// a transfer-only proxy cannot give itself more rights
+		let add_proxy = Box::new(Call::Proxy(crate::Call::add_proxy(3, ProxyType::Any)));
+		assert_noop!(Proxy::proxy(Origin::signed(3), 1, add_proxy), Error::<Test>::Unproxyable);
+		// and other accounts cannot proxy at all
+		assert_noop!(Proxy::proxy(Origin::signed(2), 1, transfer(2, 2)), Error::<Test>::NotProxy);
+	});
+}
+
+#[test]
+fn failing_proxied_calls_do_not_fail_the_proxy_call() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Proxy::add_proxy(Origin::signed(1), 3, ProxyType::Any));
+		assert_ok!(Proxy::proxy(Origin::signed(3), 1, transfer(2, 100)));
+		assert_eq!(Balances::free_balance(1), 7);
+		assert_eq!(Balances::free_balance(2), 10);
+	});
+}
@@ -0,0 +1,282 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// A FRAME pallet dispatching calls at future blocks.
+///
+/// Root schedules a call for a block, optionally under a name, with a priority and optionally
+/// repeating a number of times every period. At the beginning of that block the scheduled calls
+/// are dispatched from the root origin in order of priority, as long as they fit within
+/// `MaximumWeight`. Calls that do not fit are postponed to the next block, unless their priority
+/// value is at most `HARD_DEADLINE`. The weight of the dispatched calls is added to the weight of
+/// the block. Scheduled calls can be cancelled by their address or name.
+
+use sp_std::prelude::*;
+use codec::{Encode, Decode};
+use frame_support::{decl_module, decl_storage, decl_event, decl_error, ensure, Parameter};
+use frame_support::dispatch::DispatchResult;
+use frame_support::traits::Get;
+use frame_support::weights::{
+	DispatchClass, FunctionOf, GetDispatchInfo, SimpleDispatchInfo, Weight,
+};
+use sp_runtime::RuntimeDebug;
+use sp_runtime::traits::{Dispatchable, One, Zero};
+use system::ensure_root;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+/// The priority of a scheduled call. Lower values are dispatched first.
+pub type Priority = u8;
+
+/// The highest priority.
+pub const HIGHEST_PRIORITY: Priority = 0;
+/// Calls with a priority value of at most this one are dispatched at their block even if they
+/// exceed `MaximumWeight`.
+pub const HARD_DEADLINE: Priority = 63;
+/// The lowest priority.
+pub const LOWEST_PRIORITY: Priority = 255;
+
+/// A repetition of a scheduled call: the number of blocks between dispatches, and the number of
+/// dispatches in total.
+pub type Period<BlockNumber> = (BlockNumber, u32);
+
+/// The address of a scheduled call: its block and its index in the block's agenda.
+pub type TaskAddress<BlockNumber> = (BlockNumber, u32);
+
+/// A scheduled call.
+#[derive(Clone, RuntimeDebug, Encode, Decode)]
+pub struct Scheduled<Call, BlockNumber> {
+	/// The name of the call, if it was scheduled with one.
+	pub maybe_id: Option<Vec<u8>>,
+	/// The priority of the call.
+	pub priority: Priority,
+	/// The call to dispatch.
+	pub call: Call,
+	/// How the call repeats, if it does.
+	pub maybe_periodic: Option<Period<BlockNumber>>,
+}
+
+/// The pallet's configuration trait.
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The overarching call type, which scheduled calls are made of.
+	type Call: Parameter + Dispatchable<Origin=<Self as system::Trait>::Origin> + GetDispatchInfo;
+
+	/// The maximum weight of the calls dispatched at the beginning of a block, apart from those
+	/// with a hard deadline.
+	type MaximumWeight: Get<Weight>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Scheduler {
+		/// The calls scheduled for each block. Cancelled and dispatched calls leave an empty slot,
+		/// so that the addresses of the other calls stay valid.
+		pub Agenda get(fn agenda):
+			map hasher(twox_64_concat) T::BlockNumber
+			=> Vec<Option<Scheduled<<T as Trait>::Call, T::BlockNumber>>>;
+
+		/// The addresses of the named calls.
+		pub Lookup get(fn lookup):
+			map hasher(twox_64_concat) Vec<u8> => Option<TaskAddress<T::BlockNumber>>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where BlockNumber = <T as system::Trait>::BlockNumber {
+		/// A call was scheduled. [when, index]
+		Scheduled(BlockNumber, u32),
+		/// A scheduled call was cancelled. [when, index]
+		Canceled(BlockNumber, u32),
+		/// A scheduled call was dispatched. [when, index, id, result]
+		Dispatched(BlockNumber, u32, Option<Vec<u8>>, DispatchResult),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// There is no scheduled call at the given address or with the given name
+		NotFound,
+		/// A call with the given name is already scheduled
+		DuplicateName,
+		/// Calls can only be scheduled for future blocks
+		TargetBlockNumberInPast,
+		/// A periodic call must repeat at least one block later
+		ZeroPeriod,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: <T as system::Trait>::Origin {
+		type Error = Error<T>;
+
+		const MaximumWeight: Weight = T::MaximumWeight::get();
+
+		fn deposit_event() = default;
+
+		/// Schedule `call` to be dispatched from the root origin at block `when`.
+		///
+		/// The origin must be root. The weight includes the weight of `call`.
+		#[weight = FunctionOf(
+			|args: (
+				&T::BlockNumber,
+				&Option<Period<T::BlockNumber>>,
+				&Priority,
+				&Box<<T as Trait>::Call>,
+			)| args.3.get_dispatch_info().weight.saturating_add(100_000),
+			DispatchClass::Normal,
+			true
+		)]
+		pub fn schedule(
+			origin,
+			when: T::BlockNumber,
+			maybe_periodic: Option<Period<T::BlockNumber>>,
+			priority: Priority,
+			call: Box<<T as Trait>::Call>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::do_schedule(None, when, maybe_periodic, priority, *call)
+		}
+
+		/// Cancel the call scheduled at `index` of the agenda of block `when`.
+		///
+		/// The origin must be root.
+		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
+		pub fn cancel(origin, when: T::BlockNumber, index: u32) -> DispatchResult {
+			ensure_root(origin)?;
+			let scheduled = Self::take_task(when, index).ok_or(Error::<T>::NotFound)?;
+			if let Some(id) = scheduled.maybe_id {
+				Lookup::<T>::remove(id);
+			}
+			Self::deposit_event(RawEvent::Canceled(when, index));
+			Ok(())
+		}
+
+		/// Schedule `call` under the name `id` to be dispatched from the root origin at block `when`.
+		///
+		/// The origin must be root. The weight includes the weight of `call`.
+		#[weight = FunctionOf(
+			|args: (
+				&Vec<u8>,
+				&T::BlockNumber,
+				&Option<Period<T::BlockNumber>>,
+				&Priority,
+				&Box<<T as Trait>::Call>,
+			)| args.4.get_dispatch_info().weight.saturating_add(100_000),
+			DispatchClass::Normal,
+			true
+		)]
+		pub fn schedule_named(
+			origin,
+			id: Vec<u8>,
+			when: T::BlockNumber,
+			maybe_periodic: Option<Period<T::BlockNumber>>,
+			priority: Priority,
+			call: Box<<T as Trait>::Call>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(!Lookup::<T>::contains_key(&id), Error::<T>::DuplicateName);
+			Self::do_schedule(Some(id), when, maybe_periodic, priority, *call)
+		}
+
+		/// Cancel the call scheduled under the name `id`.
+		///
+		/// The origin must be root.
+		#[weight = SimpleDispatchInfo::FixedNormal(100_000)]
+		pub fn cancel_named(origin, id: Vec<u8>) -> DispatchResult {
+			ensure_root(origin)?;
+			let (when, index) = Lookup::<T>::take(&id).ok_or(Error::<T>::NotFound)?;
+			Self::take_task(when, index);
+			Self::deposit_event(RawEvent::Canceled(when, index));
+			Ok(())
+		}
+
+		fn on_initialize(now: T::BlockNumber) {
+			let limit = T::MaximumWeight::get();
+
+			let mut queued = Agenda::<T>::take(now).into_iter()
+				.enumerate()
+				.filter_map(|(index, s)| s.map(|s| (index as u32, s)))
+				.collect::<Vec<_>>();
+			// The sort is stable, so calls of the same priority keep the order they were
+			// scheduled in.
+			queued.sort_by_key(|(_, s)| s.priority);
+
+			let mut total_weight: Weight = 0;
+			for (order, (index, mut s)) in queued.into_iter().enumerate() {
+				let weight = s.call.get_dispatch_info().weight;
+				let fits = total_weight.saturating_add(weight) <= limit;
+				// The first call is always dispatched, so that a single heavy call cannot stall
+				// the agenda.
+				if order > 0 && !fits && s.priority > HARD_DEADLINE {
+					Self::place_task(now + One::one(), s);
+					continue;
+				}
+				total_weight = total_weight.saturating_add(weight);
+
+				let result = s.call.clone().dispatch(system::RawOrigin::Root.into());
+				Self::deposit_event(RawEvent::Dispatched(now, index, s.maybe_id.clone(), result));
+
+				match s.maybe_periodic {
+					Some((period, count)) if count > 1 => {
+						s.maybe_periodic = Some((period, count - 1));
+						Self::place_task(now + period, s);
+					}
+					_ => if let Some(id) = s.maybe_id {
+						Lookup::<T>::remove(id);
+					},
+				}
+			}
+			// The dispatched calls are not extrinsics, so their weight is added to the block's here.
+			<system::Module<T>>::register_extra_weight_unchecked(total_weight);
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	fn do_schedule(
+		maybe_id: Option<Vec<u8>>,
+		when: T::BlockNumber,
+		maybe_periodic: Option<Period<T::BlockNumber>>,
+		priority: Priority,
+		call: <T as Trait>::Call,
+	) -> DispatchResult {
+		ensure!(when > <system::Module<T>>::block_number(), Error::<T>::TargetBlockNumberInPast);
+		if let Some((period, _)) = maybe_periodic {
+			ensure!(!period.is_zero(), Error::<T>::ZeroPeriod);
+		}
+
+		let index = Self::place_task(when, Scheduled { maybe_id, priority, call, maybe_periodic });
+		Self::deposit_event(RawEvent::Scheduled(when, index));
+		Ok(())
+	}
+
+	/// Append `scheduled` to the agenda of block `when`, returning its index.
+	fn place_task(
+		when: T::BlockNumber,
+		scheduled: Scheduled<<T as Trait>::Call, T::BlockNumber>,
+	) -> u32 {
+		let maybe_id = scheduled.maybe_id.clone();
+		let index = Agenda::<T>::mutate(when, |agenda| {
+			agenda.push(Some(scheduled));
+			agenda.len() as u32 - 1
+		});
+		if let Some(id) = maybe_id {
+			Lookup::<T>::insert(id, (when, index));
+		}
+		index
+	}
+
+	/// Remove the call at `index` of the agenda of block `when`, if there is one.
+	fn take_task(
+		when: T::BlockNumber,
+		index: u32,
+	) -> Option<Scheduled<<T as Trait>::Call, T::BlockNumber>> {
+		Agenda::<T>::mutate(when, |agenda| {
+			agenda.get_mut(index as usize).and_then(Option::take)
+		})
+	}
+}
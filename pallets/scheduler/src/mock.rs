@@ -0,0 +1,112 @@
+// Creating mock runtime here
+
+use crate as scheduler;
+use crate::{Module, Trait};
+use std::cell::RefCell;
+use sp_core::H256;
+use frame_support::{impl_outer_origin, impl_outer_dispatch, parameter_types, weights::Weight};
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup, OnInitialize}, testing::Header, Perbill,
+};
+
+thread_local! {
+	static LOG: RefCell<Vec<u32>> = RefCell::new(Vec::new());
+}
+
+/// The values logged by `logger::log`, in order.
+pub fn log() -> Vec<u32> {
+	LOG.with(|log| log.borrow().clone())
+}
+
+/// A pallet with a root-only call of a given weight, to be scheduled.
+pub mod logger {
+	use super::LOG;
+	use frame_support::{decl_module, weights::{DispatchClass, FunctionOf, Weight}};
+	use system::ensure_root;
+
+	pub trait Trait: system::Trait {}
+
+	decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+			#[weight = FunctionOf(|args: (&u32, &Weight)| *args.1, DispatchClass::Normal, true)]
+			fn log(origin, i: u32, _weight: Weight) {
+				ensure_root(origin)?;
+				LOG.with(|log| log.borrow_mut().push(i));
+			}
+		}
+	}
+}
+
+impl_outer_origin! {
+	pub enum Origin for Test {}
+}
+
+impl_outer_dispatch! {
+	pub enum Call for Test where origin: Origin {
+		logger::Logger,
+		scheduler::Scheduler,
+	}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1000;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+impl system::Trait for Test {
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+impl logger::Trait for Test {}
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * MaximumBlockWeight::get();
+}
+impl Trait for Test {
+	type Event = ();
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+}
+pub type System = system::Module<Test>;
+pub type Logger = logger::Module<Test>;
+pub type Scheduler = Module<Test>;
+
+/// A scheduled `logger::log` call.
+pub fn log_call(i: u32, weight: Weight) -> Box<Call> {
+	Box::new(Call::Logger(logger::Call::log(i, weight)))
+}
+
+/// Initializes the blocks up to and including `n`.
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		Scheduler::on_initialize(System::block_number());
+	}
+}
+
+// Builds a genesis storage starting at block 1.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	let mut ext = sp_io::TestExternalities::from(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
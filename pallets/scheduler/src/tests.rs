@@ -0,0 +1,159 @@
+// Tests for the scheduler pallet
+
+use crate::{Error, HARD_DEADLINE, HIGHEST_PRIORITY, LOWEST_PRIORITY, mock::*};
+use frame_support::{assert_ok, assert_noop};
+use frame_support::weights::GetDispatchInfo;
+use sp_runtime::DispatchError;
+
+#[test]
+fn calls_are_dispatched_at_their_block() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 4, None, 127, log_call(42, 10)));
+		run_to_block(3);
+		assert!(log().is_empty());
+		run_to_block(4);
+		assert_eq!(log(), vec![42]);
+		run_to_block(10);
+		assert_eq!(log(), vec![42]);
+	});
+}
+
+#[test]
+fn periodic_calls_repeat() {
+	new_test_ext().execute_with(|| {
+		// three times, every three blocks
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 4, Some((3, 3)), 127, log_call(42, 10)));
+		run_to_block(4);
+		assert_eq!(log(), vec![42]);
+		run_to_block(6);
+		assert_eq!(log(), vec![42]);
+		run_to_block(7);
+		assert_eq!(log(), vec![42, 42]);
+		run_to_block(20);
+		assert_eq!(log(), vec![42, 42, 42]);
+	});
+}
+
+#[test]
+fn calls_are_dispatched_by_priority() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 4, None, LOWEST_PRIORITY, log_call(1, 10)));
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 4, None, HIGHEST_PRIORITY, log_call(2, 10)));
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 4, None, LOWEST_PRIORITY, log_call(3, 10)));
+		run_to_block(4);
+		assert_eq!(log(), vec![2, 1, 3]);
+	});
+}
+
+#[test]
+fn calls_exceeding_the_maximum_weight_are_postponed() {
+	new_test_ext().execute_with(|| {
+		// the maximum weight is 800
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 4, None, 127, log_call(1, 400)));
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 4, None, 127, log_call(2, 400)));
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 4, None, 127, log_call(3, 400)));
+		run_to_block(4);
+		assert_eq!(log(), vec![1, 2]);
+		run_to_block(5);
+		assert_eq!(log(), vec![1, 2, 3]);
+	});
+}
+
+#[test]
+fn dispatched_calls_are_added_to_the_block_weight() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 4, None, 127, log_call(1, 300)));
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 4, None, 127, log_call(2, 200)));
+		run_to_block(3);
+		assert_eq!(System::all_extrinsics_weight(), 0);
+		run_to_block(4);
+		assert_eq!(System::all_extrinsics_weight(), 500);
+	});
+}
+
+#[test]
+fn scheduling_weighs_the_scheduled_call() {
+	let schedule = crate::Call::<Test>::schedule(4, None, 127, log_call(1, 400));
+	assert_eq!(schedule.get_dispatch_info().weight, 100_400);
+	let schedule_named = crate::Call::<Test>::schedule_named(b"a".to_vec(), 4, None, 127, log_call(1, 400));
+	assert_eq!(schedule_named.get_dispatch_info().weight, 100_400);
+}
+
+#[test]
+fn calls_with_a_hard_deadline_are_never_postponed() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 4, None, HARD_DEADLINE, log_call(1, 600)));
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 4, None, HIGHEST_PRIORITY, log_call(2, 600)));
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 4, None, 127, log_call(3, 10)));
+		run_to_block(4);
+		assert_eq!(log(), vec![2, 1]);
+		run_to_block(5);
+		assert_eq!(log(), vec![2, 1, 3]);
+	});
+}
+
+#[test]
+fn calls_can_be_cancelled() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 4, None, 127, log_call(1, 10)));
+		assert_ok!(Scheduler::schedule(Origin::ROOT, 4, None, 127, log_call(2, 10)));
+		assert_ok!(Scheduler::cancel(Origin::ROOT, 4, 0));
+		assert_noop!(Scheduler::cancel(Origin::ROOT, 4, 0), Error::<Test>::NotFound);
+		run_to_block(4);
+		assert_eq!(log(), vec![2]);
+	});
+}
+
+#[test]
+fn named_calls_can_be_cancelled() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Scheduler::schedule_named(Origin::ROOT, b"a".to_vec(), 4, Some((3, 3)), 127, log_call(1, 10)));
+		assert_noop!(
+			Scheduler::schedule_named(Origin::ROOT, b"a".to_vec(), 5, None, 127, log_call(2, 10)),
+			Error::<Test>::DuplicateName
+		);
+		run_to_block(4);
+		assert_eq!(log(), vec![1]);
+		// the name follows the call to its next block
+		assert_eq!(Scheduler::lookup(b"a".to_vec()), Some((7, 0)));
+		assert_ok!(Scheduler::cancel_named(Origin::ROOT, b"a".to_vec()));
+		assert_noop!(Scheduler::cancel_named(Origin::ROOT, b"a".to_vec()), Error::<Test>::NotFound);
+		run_to_block(20);
+		assert_eq!(log(), vec![1]);
+	});
+}
+
+#[test]
+fn names_are_released_after_the_last_dispatch() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Scheduler::schedule_named(Origin::ROOT, b"a".to_vec(), 4, None, 127, log_call(1, 10)));
+		run_to_block(4);
+		assert_eq!(Scheduler::lookup(b"a".to_vec()), None);
+		assert_ok!(Scheduler::schedule_named(Origin::ROOT, b"a".to_vec(), 5, None, 127, log_call(2, 10)));
+	});
+}
+
+#[test]
+fn invalid_schedules_are_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Scheduler::schedule(Origin::ROOT, 1, None, 127, log_call(1, 10)),
+			Error::<Test>::TargetBlockNumberInPast
+		);
+		assert_noop!(
+			Scheduler::schedule(Origin::ROOT, 4, Some((0, 2)), 127, log_call(1, 10)),
+			Error::<Test>::ZeroPeriod
+		);
+	});
+}
+
+#[test]
+fn only_root_can_schedule() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Scheduler::schedule(Origin::signed(1), 4, None, 127, log_call(1, 10)),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(Scheduler::cancel_named(Origin::signed(1), b"a".to_vec()), DispatchError::BadOrigin);
+	});
+}
@@ -0,0 +1,67 @@
+//! RPC interface for the template pallet.
+
+use std::sync::Arc;
+use codec::Codec;
+use sp_blockchain::HeaderBackend;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use sp_api::ProvideRuntimeApi;
+pub use template_runtime_api::TemplateApi as TemplateRuntimeApi;
+
+/// Template pallet RPC methods.
+#[rpc]
+pub trait TemplateApi<BlockHash, AccountId> {
+	/// Returns the value stored for `who`, at the given block or the best block if none is given.
+	#[rpc(name = "template_getValue")]
+	fn get_value(&self, who: AccountId, at: Option<BlockHash>) -> Result<Option<u32>>;
+}
+
+/// An implementation of the template pallet RPC methods.
+pub struct Template<C, M> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<M>,
+}
+
+impl<C, M> Template<C, M> {
+	/// Create new `Template` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Template { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+impl<C, Block, AccountId> TemplateApi<<Block as BlockT>::Hash, AccountId> for Template<C, Block>
+where
+	Block: BlockT,
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: TemplateRuntimeApi<Block, AccountId>,
+	AccountId: Codec,
+{
+	fn get_value(&self, who: AccountId, at: Option<<Block as BlockT>::Hash>) -> Result<Option<u32>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.get_value(&at, who).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(Error::RuntimeError.into()),
+			message: "Unable to query value.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+}
@@ -0,0 +1,15 @@
+//! Runtime API definition for the template pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+	/// The API to read the state of the template pallet.
+	pub trait TemplateApi<AccountId> where
+		AccountId: Codec,
+	{
+		/// Get the value stored for the given account, if any.
+		fn get_value(who: AccountId) -> Option<u32>;
+	}
+}
@@ -0,0 +1,35 @@
+//! Benchmarks for the template pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{benchmarks, account};
+use system::RawOrigin;
+
+const SEED: u32 = 0;
+
+benchmarks! {
+	_ { }
+
+	// The stored value does not affect the cost of any dispatchable, so the only component of
+	// each benchmark is a single point. `benchmarks!` only takes samples at the steps of the
+	// components, so a benchmark needs at least one of them to be timed at all.
+	do_something {
+		let s in 0 .. 0;
+		let caller: T::AccountId = account("caller", 0, SEED);
+		// overwrite an existing value so the old value is read and returned
+		<Something<T>>::insert(&caller, 0);
+	}: _(RawOrigin::Signed(caller), s)
+
+	clear_something {
+		let s in 0 .. 0;
+		let caller: T::AccountId = account("caller", 0, SEED);
+		<Something<T>>::insert(&caller, s);
+	}: _(RawOrigin::Signed(caller))
+
+	cause_error {
+		let s in 0 .. 0;
+		let caller: T::AccountId = account("caller", 0, SEED);
+		<Something<T>>::insert(&caller, s);
+	}: _(RawOrigin::Signed(caller))
+}
@@ -9,7 +9,9 @@
 /// For more guidance on Substrate FRAME, see the example pallet
 /// https://github.com/paritytech/substrate/blob/master/frame/example/src/lib.rs
 
+use codec::{Encode, Decode};
 use frame_support::{decl_module, decl_storage, decl_event, decl_error, dispatch};
+use frame_support::weights::SimpleDispatchInfo;
 use system::ensure_signed;
 
 #[cfg(test)]
@@ -18,12 +20,37 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+mod benchmarking;
+pub mod migration;
+pub mod weights;
+
+pub use weights::WeightInfo;
+
 /// The pallet's configuration trait.
 pub trait Trait: system::Trait {
 	// Add other types and constants required to configure this pallet.
 
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// Weight information for the extrinsics in this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+/// The layouts this pallet's storage has had, so that a runtime upgrade knows which migrations
+/// to run. Add a variant, and a migration from the previous one, whenever the layout changes.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum Releases {
+	/// A single `Something` value, shared by every signer.
+	V1_0_0,
+	/// A `Something` slot per signer.
+	V2_0_0,
+}
+
+impl Default for Releases {
+	// Chains that predate the storage version store none, and have the original layout.
+	fn default() -> Self { Releases::V1_0_0 }
 }
 
 // This pallet's storage items.
@@ -33,9 +60,27 @@ decl_storage! {
 	// ---------------------------------vvvvvvvvvvvvvv
 	trait Store for Module<T: Trait> as TemplateModule {
 		// Just a dummy storage item.
-		// Here we are declaring a StorageValue, `Something` as a Option<u32>
-		// `get(fn something)` is the default getter which returns either the stored `u32` or `None` if nothing stored
-		Something get(fn something): Option<u32>;
+		// Here we are declaring a StorageMap, `Something`, from an account to a `u32`, so that
+		// every signer owns its own slot instead of sharing one global value.
+		// `get(fn something)` is the default getter which returns either the `u32` stored for the
+		// given account or `None` if nothing stored
+		Something get(fn something): map hasher(blake2_128_concat) T::AccountId => Option<u32>;
+
+		/// The layout of this pallet's storage. New chains start with the latest one.
+		StorageVersion get(fn storage_version) build(|_| Releases::V2_0_0): Releases;
+	}
+	add_extra_genesis {
+		/// Value given to every account in `accounts` that does not specify its own.
+		config(initial_value): Option<u32>;
+		/// Accounts to seed, optionally with their own value overriding `initial_value`.
+		config(accounts): Vec<(T::AccountId, Option<u32>)>;
+		build(|config: &GenesisConfig<T>| {
+			for (who, value) in &config.accounts {
+				if let Some(value) = value.or(config.initial_value) {
+					<Something<T>>::insert(who, value);
+				}
+			}
+		});
 	}
 }
 
@@ -43,9 +88,12 @@ decl_storage! {
 decl_event!(
 	pub enum Event<T> where AccountId = <T as system::Trait>::AccountId {
 		/// Just a dummy event.
-		/// Event `Something` is declared with a parameter of the type `u32` and `AccountId`
+		/// Event `SomethingStored` is declared with the `AccountId` owning the slot, the previous
+		/// value (if any) and the newly stored value.
 		/// To emit this event, we call the deposit function, from our runtime functions
-		SomethingStored(u32, AccountId),
+		SomethingStored(AccountId, Option<u32>, u32),
+		/// The value stored for an account was removed. [who, old value]
+		SomethingCleared(AccountId, u32),
 	}
 );
 
@@ -72,33 +120,55 @@ decl_module! {
 		// this is needed only if you are using events in your pallet
 		fn deposit_event() = default;
 
+		// Migrates the storage of chains upgrading from an older layout. An old `Something` value
+		// that a custom migration of the runtime did not give to an account is dropped.
+		fn on_runtime_upgrade() {
+			migration::migrate_to_per_account::<T>(None);
+		}
+
 		/// Just a dummy entry point.
 		/// function that can be called by the external world as an extrinsics call
-		/// takes a parameter of the type `AccountId`, stores it, and emits an event
+		/// takes a parameter of the type `u32`, stores it in the signer's slot, and emits an event
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::do_something())]
 		pub fn do_something(origin, something: u32) -> dispatch::DispatchResult {
 			// Check it was signed and get the signer. See also: ensure_root and ensure_none
 			let who = ensure_signed(origin)?;
 
 			// Code to execute when something calls this.
-			// For example: the following line stores the passed in u32 in the storage
-			Something::put(something);
+			// For example: the following line stores the passed in u32 in the signer's slot,
+			// returning whatever was there before
+			let old = <Something<T>>::mutate(&who, |slot| slot.replace(something));
 
 			// Here we are raising the Something event
-			Self::deposit_event(RawEvent::SomethingStored(something, who));
+			Self::deposit_event(RawEvent::SomethingStored(who, old, something));
+			Ok(())
+		}
+
+		/// Removes the value stored in the signer's slot, and emits an event
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::clear_something())]
+		pub fn clear_something(origin) -> dispatch::DispatchResult {
+			// Check it was signed and get the signer. See also: ensure_root and ensure_none
+			let who = ensure_signed(origin)?;
+
+			let old = <Something<T>>::take(&who).ok_or(Error::<T>::NoneValue)?;
+
+			Self::deposit_event(RawEvent::SomethingCleared(who, old));
 			Ok(())
 		}
 
 		/// Another dummy entry point.
-		/// takes no parameters, attempts to increment storage value, and possibly throws an error
+		/// takes no parameters, attempts to increment the signer's value, and possibly throws an error
+		#[weight = SimpleDispatchInfo::FixedNormal(T::WeightInfo::cause_error())]
 		pub fn cause_error(origin) -> dispatch::DispatchResult {
 			// Check it was signed and get the signer. See also: ensure_root and ensure_none
-			let _who = ensure_signed(origin)?;
+			let who = ensure_signed(origin)?;
 
-			match Something::get() {
+			match <Something<T>>::get(&who) {
 				None => Err(Error::<T>::NoneValue)?,
 				Some(old) => {
 					let new = old.checked_add(1).ok_or(Error::<T>::StorageOverflow)?;
-					Something::put(new);
+					<Something<T>>::insert(&who, new);
+					Self::deposit_event(RawEvent::SomethingStored(who, Some(old), new));
 					Ok(())
 				},
 			}
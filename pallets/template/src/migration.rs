@@ -0,0 +1,73 @@
+//! Migrations of the template pallet's storage between the layouts of `Releases`.
+//!
+//! Migrations run from `on_runtime_upgrade`, either the pallet's own or a custom one of the
+//! runtime, and check `StorageVersion` so that running them again does nothing. Since that hook
+//! returns no weight, each migration registers the weight it consumed with the system pallet,
+//! to be accounted to the block of the upgrade. With the
+//! `try-runtime` feature, each migration also comes with checks of the storage before and after
+//! it, for dry runs of runtime upgrades.
+
+#[cfg(any(feature = "try-runtime", test))]
+use frame_support::{ensure, storage::migration::get_storage_value};
+use frame_support::storage::migration::take_storage_value;
+use frame_support::weights::Weight;
+use system::Module as System;
+use crate::{Releases, Something, StorageVersion, Trait};
+use crate::weights::{DB_READ, DB_WRITE};
+
+/// Migrate from a single `Something` value to a slot per account (`V1_0_0` to `V2_0_0`).
+///
+/// The single value belonged to no account in particular: it is moved to the slot of `owner`,
+/// unless that slot is already taken, and dropped otherwise.
+pub fn migrate_to_per_account<T: Trait>(owner: Option<&T::AccountId>) {
+	<System<T>>::register_extra_weight_unchecked(migrate::<T>(owner));
+}
+
+/// Run `migrate_to_per_account`, returning the weight consumed.
+fn migrate<T: Trait>(owner: Option<&T::AccountId>) -> Weight {
+	if StorageVersion::get() != Releases::V1_0_0 {
+		return DB_READ;
+	}
+
+	// The single value lived at the map's prefix, which no slot of the map uses.
+	let old = take_storage_value::<u32>(b"TemplateModule", b"Something", &[]);
+	if let (Some(value), Some(owner)) = (old, owner) {
+		if !<Something<T>>::contains_key(owner) {
+			<Something<T>>::insert(owner, value);
+		}
+	}
+	StorageVersion::put(Releases::V2_0_0);
+
+	DB_READ.saturating_mul(3).saturating_add(DB_WRITE.saturating_mul(3))
+}
+
+/// Check the storage before `migrate_to_per_account`, returning the single value it will move, if
+/// it will run at all.
+#[cfg(any(feature = "try-runtime", test))]
+pub fn pre_migrate_to_per_account<T: Trait>() -> Result<Option<u32>, &'static str> {
+	if StorageVersion::get() != Releases::V1_0_0 {
+		return Ok(None);
+	}
+	Ok(get_storage_value::<u32>(b"TemplateModule", b"Something", &[]))
+}
+
+/// Check the storage after `migrate_to_per_account` was given `owner`, with the value returned
+/// by `pre_migrate_to_per_account`.
+#[cfg(any(feature = "try-runtime", test))]
+pub fn post_migrate_to_per_account<T: Trait>(
+	old_value: Option<u32>,
+	owner: Option<&T::AccountId>,
+) -> Result<(), &'static str> {
+	ensure!(StorageVersion::get() == Releases::V2_0_0, "the storage version was not updated");
+	if old_value.is_some() {
+		ensure!(
+			get_storage_value::<u32>(b"TemplateModule", b"Something", &[]).is_none(),
+			"the single Something value was not removed"
+		);
+		// The owner gets the single value, or keeps its own.
+		if let Some(owner) = owner {
+			ensure!(<Something<T>>::contains_key(owner), "the owner has no Something value");
+		}
+	}
+	Ok(())
+}
@@ -45,7 +45,9 @@ impl system::Trait for Test {
 }
 impl Trait for Test {
 	type Event = ();
+	type WeightInfo = ();
 }
+pub type System = system::Module<Test>;
 pub type TemplateModule = Module<Test>;
 
 // This function basically just builds a genesis storage key/value store according to
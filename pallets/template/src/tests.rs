@@ -1,7 +1,9 @@
 // Tests to be written here
 
-use crate::{Error, mock::*};
+use crate::{Error, GenesisConfig, Releases, migration, mock::*};
 use frame_support::{assert_ok, assert_noop};
+use frame_support::storage::migration::{get_storage_value, put_storage_value};
+use sp_runtime::traits::OnRuntimeUpgrade;
 
 #[test]
 fn it_works_for_default_value() {
@@ -10,7 +12,46 @@ fn it_works_for_default_value() {
 		// calling the `do_something` function with a value 42
 		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
 		// asserting that the stored value is equal to what we stored
-		assert_eq!(TemplateModule::something(), Some(42));
+		assert_eq!(TemplateModule::something(1), Some(42));
+	});
+}
+
+#[test]
+fn values_are_stored_per_account() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		assert_ok!(TemplateModule::do_something(Origin::signed(2), 7));
+		// each signer owns its own slot
+		assert_eq!(TemplateModule::something(1), Some(42));
+		assert_eq!(TemplateModule::something(2), Some(7));
+		assert_eq!(TemplateModule::something(3), None);
+
+		// overwriting one slot leaves the others untouched
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 43));
+		assert_eq!(TemplateModule::something(1), Some(43));
+		assert_eq!(TemplateModule::something(2), Some(7));
+	});
+}
+
+#[test]
+fn clear_something_removes_only_the_signers_value() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		assert_ok!(TemplateModule::do_something(Origin::signed(2), 7));
+
+		assert_ok!(TemplateModule::clear_something(Origin::signed(1)));
+		assert_eq!(TemplateModule::something(1), None);
+		assert_eq!(TemplateModule::something(2), Some(7));
+	});
+}
+
+#[test]
+fn correct_error_for_clearing_none_value() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::clear_something(Origin::signed(1)),
+			Error::<Test>::NoneValue
+		);
 	});
 }
 
@@ -24,3 +65,152 @@ fn correct_error_for_none_value() {
 		);
 	});
 }
+
+#[test]
+fn cause_error_increments_the_signers_value() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		assert_ok!(TemplateModule::cause_error(Origin::signed(1)));
+		assert_eq!(TemplateModule::something(1), Some(43));
+		// another account has no value to increment
+		assert_noop!(
+			TemplateModule::cause_error(Origin::signed(2)),
+			Error::<Test>::NoneValue
+		);
+	});
+}
+
+#[test]
+fn correct_error_for_overflow() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), u32::max_value()));
+		assert_noop!(
+			TemplateModule::cause_error(Origin::signed(1)),
+			Error::<Test>::StorageOverflow
+		);
+	});
+}
+
+#[test]
+fn genesis_config_seeds_accounts() {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	GenesisConfig::<Test> {
+		initial_value: Some(7),
+		accounts: vec![(1, None), (2, Some(3))],
+	}.assimilate_storage(&mut t).unwrap();
+
+	sp_io::TestExternalities::from(t).execute_with(|| {
+		// accounts without their own value get the initial value
+		assert_eq!(TemplateModule::something(1), Some(7));
+		// per-account entries override it
+		assert_eq!(TemplateModule::something(2), Some(3));
+		// accounts not listed start empty
+		assert_eq!(TemplateModule::something(3), None);
+	});
+}
+
+#[test]
+fn genesis_config_without_initial_value_only_seeds_explicit_entries() {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	GenesisConfig::<Test> {
+		initial_value: None,
+		accounts: vec![(1, None), (2, Some(3))],
+	}.assimilate_storage(&mut t).unwrap();
+
+	sp_io::TestExternalities::from(t).execute_with(|| {
+		assert_eq!(TemplateModule::something(1), None);
+		assert_eq!(TemplateModule::something(2), Some(3));
+	});
+}
+
+#[test]
+fn genesis_starts_with_the_latest_storage_version() {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	GenesisConfig::<Test> {
+		initial_value: None,
+		accounts: vec![],
+	}.assimilate_storage(&mut t).unwrap();
+
+	sp_io::TestExternalities::from(t).execute_with(|| {
+		assert_eq!(TemplateModule::storage_version(), Releases::V2_0_0);
+	});
+}
+
+/// Storage as left by a runtime from before `Something` was stored per account, holding
+/// `old_value` in the single `Something` value and no storage version.
+fn pre_upgrade_ext(old_value: Option<u32>) -> sp_io::TestExternalities {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| if let Some(value) = old_value {
+		put_storage_value(b"TemplateModule", b"Something", &[], value);
+	});
+	ext
+}
+
+fn old_value() -> Option<u32> {
+	get_storage_value(b"TemplateModule", b"Something", &[])
+}
+
+#[test]
+fn migration_moves_the_old_value_to_the_owner() {
+	pre_upgrade_ext(Some(42)).execute_with(|| {
+		assert_eq!(TemplateModule::storage_version(), Releases::V1_0_0);
+
+		migration::migrate_to_per_account::<Test>(Some(&1));
+		assert_eq!(TemplateModule::storage_version(), Releases::V2_0_0);
+		assert_eq!(old_value(), None);
+		assert_eq!(TemplateModule::something(1), Some(42));
+		assert_eq!(TemplateModule::something(2), None);
+	});
+}
+
+#[test]
+fn migration_keeps_a_value_the_owner_already_has() {
+	pre_upgrade_ext(Some(42)).execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 7));
+
+		migration::migrate_to_per_account::<Test>(Some(&1));
+		assert_eq!(old_value(), None);
+		assert_eq!(TemplateModule::something(1), Some(7));
+	});
+}
+
+#[test]
+fn runtime_upgrade_drops_the_old_value() {
+	pre_upgrade_ext(Some(42)).execute_with(|| {
+		TemplateModule::on_runtime_upgrade();
+		assert_eq!(TemplateModule::storage_version(), Releases::V2_0_0);
+		assert_eq!(old_value(), None);
+		assert_eq!(TemplateModule::something(1), None);
+		// the weight of the migration is accounted to the block
+		assert!(System::all_extrinsics_weight() > 0);
+	});
+}
+
+#[test]
+fn migration_only_runs_once() {
+	pre_upgrade_ext(None).execute_with(|| {
+		TemplateModule::on_runtime_upgrade();
+		assert_eq!(TemplateModule::storage_version(), Releases::V2_0_0);
+
+		// a value at the old location is left alone by later upgrades
+		put_storage_value(b"TemplateModule", b"Something", &[], 42u32);
+		migration::migrate_to_per_account::<Test>(Some(&1));
+		assert_eq!(old_value(), Some(42));
+		assert_eq!(TemplateModule::something(1), None);
+	});
+}
+
+#[test]
+fn migration_checks_pass_on_a_migrated_storage() {
+	pre_upgrade_ext(Some(42)).execute_with(|| {
+		let old = migration::pre_migrate_to_per_account::<Test>().unwrap();
+		assert_eq!(old, Some(42));
+		// the checks fail until the migration ran
+		assert!(migration::post_migrate_to_per_account::<Test>(old, Some(&1)).is_err());
+
+		migration::migrate_to_per_account::<Test>(Some(&1));
+		assert_ok!(migration::post_migrate_to_per_account::<Test>(old, Some(&1)));
+		// nothing is left to migrate
+		assert_eq!(migration::pre_migrate_to_per_account::<Test>(), Ok(None));
+	});
+}
@@ -0,0 +1,51 @@
+//! Weights for the template pallet.
+//!
+//! Every dispatchable does a fixed amount of computation plus one read and one write of its
+//! caller's `Something` slot, so its weight is a base cost plus the cost of those database
+//! accesses.
+//!
+//! The base costs and database weights below are conservative estimates, not measurements, until
+//! they are replaced with the results of the `runtime-benchmarks` suite in `benchmarking.rs`. On
+//! the reference hardware of the chain, with the node built with `--features runtime-benchmarks`,
+//! each dispatchable is measured with:
+//!
+//! ```text
+//! node-template benchmark --chain dev --execution wasm --pallet template \
+//!     --extrinsic <name> --steps 1 --repeat 100 --json
+//! ```
+//!
+//! The weight of a dispatchable is then the median `extrinsic_time` of its samples, in
+//! nanoseconds, as the runtime's `MaximumBlockWeight` of `1_000_000_000` stands for a second.
+
+use frame_support::weights::Weight;
+
+/// Weight of reading a single storage item.
+pub const DB_READ: Weight = 25_000;
+/// Weight of writing (or removing) a single storage item.
+pub const DB_WRITE: Weight = 100_000;
+
+/// Weight functions needed by the template pallet.
+pub trait WeightInfo {
+	fn do_something() -> Weight;
+	fn clear_something() -> Weight;
+	fn cause_error() -> Weight;
+}
+
+/// Estimated weights, assuming a RocksDB backend.
+impl WeightInfo for () {
+	fn do_something() -> Weight {
+		(20_000 as Weight)
+			.saturating_add(DB_READ)
+			.saturating_add(DB_WRITE)
+	}
+	fn clear_something() -> Weight {
+		(19_000 as Weight)
+			.saturating_add(DB_READ)
+			.saturating_add(DB_WRITE)
+	}
+	fn cause_error() -> Weight {
+		(21_000 as Weight)
+			.saturating_add(DB_READ)
+			.saturating_add(DB_WRITE)
+	}
+}
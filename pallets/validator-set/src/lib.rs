@@ -0,0 +1,160 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// A FRAME pallet letting root change the validators elected by another session manager.
+///
+/// Root (for example through the sudo pallet) adds and removes validators. `WithChanges` wraps the
+/// session manager electing the validators, usually the staking pallet, and applies these changes
+/// to every set it elects: added validators validate whether or not they were elected, removed
+/// ones do not validate even if they were. `OnChange` is notified of every change, so that the
+/// runtime can have a new set elected at the next session rotation rather than at the next
+/// election.
+
+use sp_std::prelude::*;
+use frame_support::{decl_module, decl_storage, decl_event, decl_error, dispatch, ensure};
+use frame_support::weights::SimpleDispatchInfo;
+use session::historical;
+use sp_staking::SessionIndex;
+use system::ensure_root;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+/// Notified when root changes the validators.
+pub trait OnChange {
+	/// The validators were changed.
+	fn on_change();
+}
+
+impl OnChange for () {
+	fn on_change() {}
+}
+
+/// The pallet's configuration trait.
+pub trait Trait: system::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// Notified when root changes the validators, for example to elect a new set early.
+	type OnChange: OnChange;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as ValidatorSet {
+		/// The validators root added, which validate whether or not they are elected.
+		Added get(fn added) config(): Vec<T::AccountId>;
+		/// The validators root removed, which do not validate even if they are elected.
+		Removed get(fn removed): Vec<T::AccountId>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where AccountId = <T as system::Trait>::AccountId {
+		/// A validator was added to the set. [who]
+		ValidatorAdded(AccountId),
+		/// A validator was removed from the set. [who]
+		ValidatorRemoved(AccountId),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// The account was already added as a validator
+		AlreadyValidator,
+		/// Root already removed the account, which will not validate even if it is elected
+		AlreadyRemoved,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Make `validator` validate whether or not it is elected, undoing its removal if root
+		/// removed it. Takes effect with the next set of validators.
+		///
+		/// The origin must be root.
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		pub fn add_validator(origin, validator: T::AccountId) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+
+			let mut added = Self::added();
+			ensure!(!added.contains(&validator), Error::<T>::AlreadyValidator);
+			added.push(validator.clone());
+			<Added<T>>::put(added);
+			<Removed<T>>::mutate(|removed| removed.retain(|v| v != &validator));
+
+			T::OnChange::on_change();
+			Self::deposit_event(RawEvent::ValidatorAdded(validator));
+			Ok(())
+		}
+
+		/// Stop `validator` from validating even if it is elected, undoing its addition if root
+		/// added it. Takes effect with the next set of validators.
+		///
+		/// The origin must be root.
+		#[weight = SimpleDispatchInfo::FixedNormal(50_000)]
+		pub fn remove_validator(origin, validator: T::AccountId) -> dispatch::DispatchResult {
+			ensure_root(origin)?;
+
+			let mut removed = Self::removed();
+			ensure!(!removed.contains(&validator), Error::<T>::AlreadyRemoved);
+			removed.push(validator.clone());
+			<Removed<T>>::put(removed);
+			<Added<T>>::mutate(|added| added.retain(|v| v != &validator));
+
+			T::OnChange::on_change();
+			Self::deposit_event(RawEvent::ValidatorRemoved(validator));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Apply root's changes to the `elected` validators and their full identifications. Added
+	/// validators that were not elected get the default identification, having nothing at stake.
+	///
+	/// Changes that would leave no validators at all are ignored.
+	pub fn apply_changes<F: Default + Clone>(
+		elected: Vec<(T::AccountId, F)>,
+	) -> Vec<(T::AccountId, F)> {
+		let removed = Self::removed();
+		let mut validators = elected.iter()
+			.filter(|(v, _)| !removed.contains(v))
+			.cloned()
+			.collect::<Vec<_>>();
+		for v in Self::added() {
+			if !validators.iter().any(|(elected, _)| elected == &v) {
+				validators.push((v, F::default()));
+			}
+		}
+
+		if validators.is_empty() {
+			elected
+		} else {
+			validators
+		}
+	}
+}
+
+/// The session manager `I`, usually the staking pallet, with root's changes applied to the
+/// validators it elects.
+pub struct WithChanges<T, I>(sp_std::marker::PhantomData<(T, I)>);
+
+impl<T, I, F> historical::SessionManager<T::AccountId, F> for WithChanges<T, I> where
+	T: Trait,
+	I: historical::SessionManager<T::AccountId, F>,
+	F: Default + Clone,
+{
+	fn new_session(new_index: SessionIndex) -> Option<Vec<(T::AccountId, F)>> {
+		I::new_session(new_index).map(<Module<T>>::apply_changes)
+	}
+
+	fn end_session(end_index: SessionIndex) {
+		I::end_session(end_index)
+	}
+}
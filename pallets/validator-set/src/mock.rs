@@ -0,0 +1,97 @@
+// Creating mock runtime here
+
+use std::cell::RefCell;
+use crate::{Module, Trait, GenesisConfig, OnChange, WithChanges};
+use session::historical;
+use sp_core::H256;
+use sp_staking::SessionIndex;
+use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup}, testing::Header, Perbill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for Test {}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+impl system::Trait for Test {
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+thread_local! {
+	static ELECTED: RefCell<Option<Vec<u64>>> = RefCell::new(None);
+	static CHANGES: RefCell<u32> = RefCell::new(0);
+}
+
+/// Set the validators `Elections` elects at the next session, if any.
+pub fn elect(validators: Option<Vec<u64>>) {
+	ELECTED.with(|e| *e.borrow_mut() = validators);
+}
+
+/// The number of times `OnChange` was notified.
+pub fn changes() -> u32 {
+	CHANGES.with(|c| *c.borrow())
+}
+
+/// A session manager electing the validators given to `elect`, identified by their index.
+pub struct Elections;
+
+impl historical::SessionManager<u64, u64> for Elections {
+	fn new_session(_new_index: SessionIndex) -> Option<Vec<(u64, u64)>> {
+		ELECTED.with(|e| e.borrow().clone())
+			.map(|elected| elected.into_iter().map(|v| (v, v * 10)).collect())
+	}
+
+	fn end_session(_end_index: SessionIndex) {}
+}
+
+pub struct CountChanges;
+
+impl OnChange for CountChanges {
+	fn on_change() {
+		CHANGES.with(|c| *c.borrow_mut() += 1);
+	}
+}
+
+impl Trait for Test {
+	type Event = ();
+	type OnChange = CountChanges;
+}
+pub type ValidatorSet = Module<Test>;
+pub type Manager = WithChanges<Test, Elections>;
+
+// Builds a genesis storage in which root added validator 4, and elections elect 1, 2 and 3.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	elect(Some(vec![1, 2, 3]));
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	GenesisConfig::<Test> {
+		added: vec![4],
+	}.assimilate_storage(&mut t).unwrap();
+	t.into()
+}
@@ -0,0 +1,89 @@
+// Tests for the validator set pallet
+
+use crate::{Error, mock::*};
+use frame_support::{assert_ok, assert_noop};
+use session::historical::SessionManager;
+use sp_runtime::DispatchError;
+
+#[test]
+fn genesis_validators_are_added() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(ValidatorSet::added(), vec![4]);
+		// added validators have nothing at stake
+		assert_eq!(Manager::new_session(1), Some(vec![(1, 10), (2, 20), (3, 30), (4, 0)]));
+	});
+}
+
+#[test]
+fn changes_apply_only_to_new_elections() {
+	new_test_ext().execute_with(|| {
+		elect(None);
+		assert_eq!(Manager::new_session(1), None);
+	});
+}
+
+#[test]
+fn root_can_add_validators() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ValidatorSet::add_validator(Origin::ROOT, 5));
+		assert_eq!(changes(), 1);
+		assert_eq!(ValidatorSet::added(), vec![4, 5]);
+		assert_eq!(
+			Manager::new_session(1),
+			Some(vec![(1, 10), (2, 20), (3, 30), (4, 0), (5, 0)])
+		);
+
+		// adding an elected validator keeps its stake
+		assert_ok!(ValidatorSet::add_validator(Origin::ROOT, 1));
+		assert_eq!(
+			Manager::new_session(2),
+			Some(vec![(1, 10), (2, 20), (3, 30), (4, 0), (5, 0)])
+		);
+	});
+}
+
+#[test]
+fn root_can_remove_validators() {
+	new_test_ext().execute_with(|| {
+		// both elected and added validators can be removed
+		assert_ok!(ValidatorSet::remove_validator(Origin::ROOT, 1));
+		assert_ok!(ValidatorSet::remove_validator(Origin::ROOT, 4));
+		assert_eq!(changes(), 2);
+		assert_eq!(ValidatorSet::added(), Vec::<u64>::new());
+		assert_eq!(Manager::new_session(1), Some(vec![(2, 20), (3, 30)]));
+
+		// adding a removed validator undoes its removal
+		assert_ok!(ValidatorSet::add_validator(Origin::ROOT, 1));
+		assert_eq!(ValidatorSet::removed(), vec![4]);
+		assert_eq!(Manager::new_session(2), Some(vec![(1, 10), (2, 20), (3, 30)]));
+	});
+}
+
+#[test]
+fn changes_never_leave_no_validators() {
+	new_test_ext().execute_with(|| {
+		for v in 1..=4 {
+			assert_ok!(ValidatorSet::remove_validator(Origin::ROOT, v));
+		}
+		assert_eq!(Manager::new_session(1), Some(vec![(1, 10), (2, 20), (3, 30)]));
+	});
+}
+
+#[test]
+fn only_root_can_change_validators() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(ValidatorSet::add_validator(Origin::signed(1), 5), DispatchError::BadOrigin);
+		assert_noop!(ValidatorSet::remove_validator(Origin::signed(1), 2), DispatchError::BadOrigin);
+	});
+}
+
+#[test]
+fn correct_errors_for_repeated_changes() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(ValidatorSet::add_validator(Origin::ROOT, 4), Error::<Test>::AlreadyValidator);
+
+		assert_ok!(ValidatorSet::remove_validator(Origin::ROOT, 2));
+		assert_noop!(ValidatorSet::remove_validator(Origin::ROOT, 2), Error::<Test>::AlreadyRemoved);
+		assert_eq!(changes(), 1);
+	});
+}
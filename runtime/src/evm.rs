@@ -0,0 +1,156 @@
+//! The EVM compatibility layer, enabled by the `evm` feature.
+
+use sp_std::prelude::*;
+use sp_core::{H160, U256};
+use evm_executor::{Config, ExitReason, executor::StackExecutor};
+use evm_rpc_runtime_api::{CallResult, EvmAccount};
+use pallet_evm::{Backend, ConvertAccountId, FeeCalculator, Vicinity};
+use crate::{AccountId, Runtime};
+
+/// The EVM configuration calls are executed with.
+static ISTANBUL_CONFIG: Config = Config::istanbul();
+
+/// A fixed minimum gas price of one unit per gas.
+pub struct FixedGasPrice;
+
+impl FeeCalculator for FixedGasPrice {
+	fn min_gas_price() -> U256 {
+		U256::one()
+	}
+}
+
+/// The EVM address of `account`, the first 20 bytes of the hash of the account.
+pub fn address_of(account: &AccountId) -> H160 {
+	<Runtime as pallet_evm::Trait>::ConvertAccountId::convert_account_id(account)
+}
+
+/// The EVM nonce and balance of `address`.
+pub fn account_basic(address: H160) -> EvmAccount {
+	let account = pallet_evm::Module::<Runtime>::accounts(address);
+	EvmAccount { nonce: account.nonce, balance: account.balance }
+}
+
+/// Execute a call from `from` to `to` against the current state, without committing its changes.
+pub fn call(from: H160, to: H160, data: Vec<u8>, value: U256, gas_limit: u32) -> CallResult {
+	let vicinity = Vicinity { gas_price: U256::zero(), origin: from };
+	let backend = Backend::<Runtime>::new(&vicinity);
+	let mut executor = StackExecutor::new(&backend, gas_limit as usize, &ISTANBUL_CONFIG);
+
+	match executor.transact_call(from, to, value, data, gas_limit as usize) {
+		(ExitReason::Succeed(_), output) => CallResult::Succeed(output),
+		(ExitReason::Revert(_), output) => CallResult::Revert(output),
+		(ExitReason::Error(_), _) | (ExitReason::Fatal(_), _) => CallResult::Error,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::assert_ok;
+	use sp_keyring::AccountKeyring;
+	use crate::{Event, Origin, System, EVM};
+
+	/// The init code of `res/SimpleStorage.sol`, as compiled by solc 0.4 with
+	/// `solc -o res --bin --abi res/SimpleStorage.sol`.
+	const SIMPLE_STORAGE: &str = include_str!("../res/SimpleStorage.bin");
+
+	/// The selectors of `SimpleStorage.set(uint256)` and `SimpleStorage.get()`.
+	const SET: [u8; 4] = [0x60, 0xfe, 0x47, 0xb1];
+	const GET: [u8; 4] = [0x6d, 0x4c, 0xe6, 0x3c];
+
+	/// The ABI encoding of a `uint256` argument or return value.
+	fn word(value: u64) -> Vec<u8> {
+		let mut word = vec![0; 32];
+		U256::from(value).to_big_endian(&mut word);
+		word
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		balances::GenesisConfig::<Runtime> {
+			balances: vec![(AccountKeyring::Alice.to_account_id(), 1 << 60)],
+		}.assimilate_storage(&mut t).unwrap();
+		let mut ext = sp_io::TestExternalities::from(t);
+		// Events are not recorded at genesis.
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	fn created_contract() -> Option<H160> {
+		System::events().into_iter().filter_map(|record| match record.event {
+			Event::pallet_evm(pallet_evm::Event::Created(address)) => Some(address),
+			_ => None,
+		}).last()
+	}
+
+	#[test]
+	fn accounts_map_to_addresses() {
+		let alice = AccountKeyring::Alice.to_account_id();
+		let bob = AccountKeyring::Bob.to_account_id();
+		assert_eq!(address_of(&alice), address_of(&alice));
+		assert_ne!(address_of(&alice), address_of(&bob));
+	}
+
+	/// Deploy `SimpleStorage` from Alice's address, returning the contract's address.
+	fn deploy_simple_storage() -> H160 {
+		let alice = AccountKeyring::Alice.to_account_id();
+		assert_ok!(EVM::deposit_balance(Origin::signed(alice.clone()), 1 << 40));
+		assert_ok!(EVM::create(
+			Origin::signed(alice),
+			hex::decode(SIMPLE_STORAGE.trim()).expect("the fixture is hex"),
+			U256::zero(),
+			1_000_000,
+			U256::one(),
+		));
+		created_contract().expect("the contract was created")
+	}
+
+	#[test]
+	fn deployed_contracts_can_be_called() {
+		new_test_ext().execute_with(|| {
+			let alice = AccountKeyring::Alice.to_account_id();
+			let source = address_of(&alice);
+			let contract = deploy_simple_storage();
+			assert_eq!(account_basic(source).nonce, U256::one());
+			assert!(account_basic(source).balance < U256::from(1u128 << 40));
+
+			let mut set = SET.to_vec();
+			set.extend(word(42));
+			assert_ok!(EVM::call(
+				Origin::signed(alice),
+				contract,
+				set,
+				U256::zero(),
+				1_000_000,
+				U256::one(),
+			));
+			assert_eq!(account_basic(source).nonce, U256::from(2));
+
+			assert_eq!(
+				call(source, contract, GET.to_vec(), U256::zero(), 1_000_000),
+				CallResult::Succeed(word(42)),
+			);
+			// the call's changes are not committed
+			assert_eq!(account_basic(source).nonce, U256::from(2));
+		});
+	}
+
+	#[test]
+	fn failing_calls_are_reported() {
+		new_test_ext().execute_with(|| {
+			let source = address_of(&AccountKeyring::Alice.to_account_id());
+			let contract = deploy_simple_storage();
+
+			// running out of gas
+			assert_eq!(
+				call(source, contract, GET.to_vec(), U256::zero(), 1_000),
+				CallResult::Error,
+			);
+			// calling a function the contract does not have
+			assert_eq!(
+				call(source, contract, vec![0; 4], U256::zero(), 1_000_000),
+				CallResult::Error,
+			);
+		});
+	}
+}
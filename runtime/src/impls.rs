@@ -0,0 +1,304 @@
+//! Some configurable implementations as associated type for the runtime.
+
+use sp_std::prelude::*;
+use frame_support::traits::{Currency, Get, Imbalance, OnUnbalanced};
+use frame_support::weights::Weight;
+use sp_runtime::traits::{Convert, Saturating};
+use sp_runtime::{Fixed64, Perbill, Perquintill};
+use crate::{
+	Authorship, AvailableBlockRatio, Balance, Balances, MaximumBlockWeight, NegativeImbalance, Staking,
+	System,
+};
+
+/// Struct that handles the conversion of Balance -> `u64`. This is used for staking's election
+/// calculation.
+pub struct CurrencyToVoteHandler;
+
+impl CurrencyToVoteHandler {
+	fn factor() -> Balance { (Balances::total_issuance() / u64::max_value() as Balance).max(1) }
+}
+
+impl Convert<Balance, u64> for CurrencyToVoteHandler {
+	fn convert(x: Balance) -> u64 { (x / Self::factor()) as u64 }
+}
+
+impl Convert<u128, Balance> for CurrencyToVoteHandler {
+	fn convert(x: u128) -> Balance { x * Self::factor() }
+}
+
+/// Deposits the given imbalance into the account of the current block's author.
+pub struct Author;
+
+impl OnUnbalanced<NegativeImbalance> for Author {
+	fn on_nonzero_unbalanced(amount: NegativeImbalance) {
+		Balances::resolve_creating(&Authorship::author(), amount);
+	}
+}
+
+/// Splits transaction fees: 80% are handled by `R`, the remaining 20% go to the block author.
+pub struct DealWithFees<R>(sp_std::marker::PhantomData<R>);
+
+impl<R: OnUnbalanced<NegativeImbalance>> OnUnbalanced<NegativeImbalance> for DealWithFees<R> {
+	fn on_nonzero_unbalanced(fees: NegativeImbalance) {
+		let (rest, to_author) = fees.ration(80, 20);
+		R::on_unbalanced(rest);
+		Author::on_unbalanced(to_author);
+	}
+}
+
+/// Has the staking pallet elect a new set of validators at the next session rotation.
+pub struct ForceNewEra;
+
+impl validator_set::OnChange for ForceNewEra {
+	fn on_change() {
+		// Forcing a new era only fails for a non-root origin.
+		let _ = Staking::force_new_era(system::RawOrigin::Root.into());
+	}
+}
+
+/// A single term `(coeff_integer + coeff_frac) * weight^degree` of a `PolynomialWeightToFee`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WeightToFeeCoefficient {
+	/// The integral part of the coefficient.
+	pub coeff_integer: Balance,
+	/// The fractional part of the coefficient.
+	pub coeff_frac: Perbill,
+	/// The power `weight` is raised to.
+	pub degree: u8,
+}
+
+/// Converts weight to fee with the polynomial described by the coefficients in `C`.
+///
+/// All arithmetic saturates, so the fee of a huge weight is capped at `Balance::max_value()`.
+pub struct PolynomialWeightToFee<C>(sp_std::marker::PhantomData<C>);
+
+impl<C: Get<Vec<WeightToFeeCoefficient>>> Convert<Weight, Balance> for PolynomialWeightToFee<C> {
+	fn convert(weight: Weight) -> Balance {
+		C::get().iter().fold(0, |fee: Balance, c| {
+			let power = Balance::from(weight).saturating_pow(c.degree.into());
+			let term = power.saturating_mul(c.coeff_integer)
+				.saturating_add(c.coeff_frac * power);
+			fee.saturating_add(term)
+		})
+	}
+}
+
+/// Update the given multiplier based on the following formula
+///
+///   diff = (previous_block_weight - target_weight) / normal_weight
+///   v = 0.00004
+///   next_multiplier = multiplier + (v . diff) + (v . diff)^2 / 2
+///
+/// Where `normal_weight` is the weight available to normal transactions, `AvailableBlockRatio` of
+/// `MaximumBlockWeight`, and `target_weight` is the portion of it given as the `Get`
+/// implementation of the `T` generic type.
+/// https://research.web3.foundation/en/latest/polkadot/Token%20Economics/#relay-chain-transaction-fees
+pub struct TargetedFeeAdjustment<T>(sp_std::marker::PhantomData<T>);
+
+impl<T: Get<Perquintill>> Convert<Fixed64, Fixed64> for TargetedFeeAdjustment<T> {
+	fn convert(multiplier: Fixed64) -> Fixed64 {
+		let max_weight = AvailableBlockRatio::get() * MaximumBlockWeight::get();
+		let block_weight = System::all_extrinsics_weight().min(max_weight);
+		let target_weight = T::get() * max_weight;
+
+		// determines if the first_term is positive
+		let positive = block_weight >= target_weight;
+		let diff_abs = block_weight.max(target_weight) - block_weight.min(target_weight);
+		// safe, diff_abs cannot exceed max_weight, so the ratio is at most 1 and the lossy
+		// `Fixed64::from_rational` never saturates.
+		let diff = Fixed64::from_rational(diff_abs as i64, max_weight.max(1).into());
+		let diff_squared = diff.saturating_mul(diff);
+
+		// 0.00004 = 4/100_000 = 40_000/10^9
+		let v = Fixed64::from_rational(4, 100_000);
+		// 0.00004^2 = 16/10^10 ~= 2/10^9. Taking the future /2 into account, then it is just 1
+		// part from a billionth.
+		let v_squared_2 = Fixed64::from_rational(1, 1_000_000_000);
+
+		let first_term = v.saturating_mul(diff);
+		let second_term = v_squared_2.saturating_mul(diff_squared);
+
+		if positive {
+			// Note: this is merely bounded by how big the multiplier and the inner value can go,
+			// not by any economical reasoning.
+			let excess = first_term.saturating_add(second_term);
+			multiplier.saturating_add(excess)
+		} else {
+			// Defensive-only: first_term > second_term. Safe subtraction.
+			let negative = first_term.saturating_sub(second_term);
+			multiplier.saturating_sub(negative)
+				// despite the fact that apply_to saturates weight (final fee cannot go below 0)
+				// it is crucially important to stop here and don't further reduce the weight fee
+				// multiplier. While at -1, it means that the network is so un-congested that all
+				// transactions have no weight fee. We stop here and only increase if the network
+				// became more busy.
+				.max(Fixed64::from_natural(-1))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_runtime::assert_eq_error_rate;
+	use crate::{Runtime, TargetBlockFullness, WeightToFeeCoefficients};
+
+	fn max() -> Weight {
+		AvailableBlockRatio::get() * MaximumBlockWeight::get()
+	}
+
+	fn target() -> Weight {
+		TargetBlockFullness::get() * max()
+	}
+
+	// The multiplier update of `TargetedFeeAdjustment`, computed with floats.
+	fn fee_multiplier_update(block_weight: Weight, previous: Fixed64) -> Fixed64 {
+		let block_weight = block_weight as f64;
+		let v: f64 = 0.00004;
+
+		// Weight available to normal transactions
+		let m = max() as f64;
+		// Ideal saturation in terms of weight
+		let ss = target() as f64;
+		// Current saturation in terms of weight
+		let s = block_weight;
+
+		let fm = v * (s/m - ss/m) + v.powi(2) * (s/m - ss/m).powi(2) / 2.0;
+		let addition_fm = Fixed64::from_parts((fm * 1_000_000_000f64).round() as i64);
+		previous.saturating_add(addition_fm)
+	}
+
+	fn run_with_system_weight<F>(w: Weight, assertions: F) where F: Fn() -> () {
+		let mut t: sp_io::TestExternalities =
+			system::GenesisConfig::default().build_storage::<Runtime>().unwrap().into();
+		t.execute_with(|| {
+			System::set_block_limits(w, 0);
+			assertions()
+		});
+	}
+
+	#[test]
+	fn fee_multiplier_update_matches_the_formula() {
+		let fm = Fixed64::from_rational(0, 1);
+		let test_set = vec![
+			(0, fm.clone()),
+			(100, fm.clone()),
+			(target(), fm.clone()),
+			(max() / 2, fm.clone()),
+			(max(), fm.clone()),
+		];
+		test_set.into_iter().for_each(|(w, fm)| {
+			run_with_system_weight(w, || {
+				assert_eq_error_rate!(
+					fee_multiplier_update(w, fm),
+					TargetedFeeAdjustment::<TargetBlockFullness>::convert(fm),
+					// Error is only 1 in 10^9
+					Fixed64::from_parts(1),
+				);
+			})
+		})
+	}
+
+	#[test]
+	fn empty_chain_simulation() {
+		// just a few txs per_block.
+		let block_weight = 0;
+		run_with_system_weight(block_weight, || {
+			let mut fm = Fixed64::default();
+			let mut iterations: u64 = 0;
+			loop {
+				let next = TargetedFeeAdjustment::<TargetBlockFullness>::convert(fm);
+				fm = next;
+				if fm == Fixed64::from_natural(-1) { break; }
+				iterations += 1;
+			}
+			// the multiplier bottoms out at -1, after which transactions pay no weight fee.
+			assert!(iterations > 0);
+		})
+	}
+
+	#[test]
+	fn congested_chain_simulation() {
+		// almost full. The entire quota of normal transactions is taken.
+		let block_weight = max() - 100;
+
+		run_with_system_weight(block_weight, || {
+			let mut fm = Fixed64::default();
+			let mut iterations: u64 = 0;
+			loop {
+				let next = TargetedFeeAdjustment::<TargetBlockFullness>::convert(fm);
+				// the multiplier only ever grows while blocks are above the target.
+				assert!(next > fm);
+				fm = next;
+				iterations += 1;
+				if fm >= Fixed64::from_natural(1) { break; }
+			}
+			// each block 75 percentage points above the target adds about 0.00003 to the multiplier,
+			// so the weight fee doubles after about 33_333 blocks, i.e. a little over two days.
+			assert!(iterations > 33_000 && iterations < 33_700);
+		});
+	}
+
+	#[test]
+	fn full_then_empty_blocks_recover() {
+		let mut fm = Fixed64::default();
+
+		// a sequence of full blocks raises the multiplier...
+		run_with_system_weight(max(), || {
+			for _ in 0..100 {
+				fm = TargetedFeeAdjustment::<TargetBlockFullness>::convert(fm);
+			}
+		});
+		let congested = fm;
+		assert!(congested > Fixed64::default());
+
+		// ...blocks at the target keep it where it is...
+		run_with_system_weight(target(), || {
+			fm = TargetedFeeAdjustment::<TargetBlockFullness>::convert(fm);
+		});
+		assert_eq!(fm, congested);
+
+		// ...and empty blocks bring it back down.
+		run_with_system_weight(0, || {
+			for _ in 0..100 {
+				fm = TargetedFeeAdjustment::<TargetBlockFullness>::convert(fm);
+			}
+		});
+		assert!(fm < congested);
+	}
+
+	#[test]
+	fn weight_to_fee_is_polynomial() {
+		let to_fee = |w| PolynomialWeightToFee::<WeightToFeeCoefficients>::convert(w);
+		let coefficients = WeightToFeeCoefficients::get();
+
+		// no weight, no fee.
+		assert_eq!(to_fee(0), 0);
+
+		// the fee is the sum of all terms.
+		let weight: Weight = 1_000_000;
+		let expected = coefficients.iter().fold(0, |fee: Balance, c| {
+			let power = (weight as Balance).pow(c.degree.into());
+			fee + power * c.coeff_integer + c.coeff_frac * power
+		});
+		assert_eq!(to_fee(weight), expected);
+
+		// heavier transactions pay more than proportionally.
+		assert!(to_fee(max()) > to_fee(max() / 2) * 2);
+	}
+
+	#[test]
+	fn weight_to_fee_saturates() {
+		frame_support::parameter_types! {
+			pub HugeCoefficients: Vec<WeightToFeeCoefficient> = vec![WeightToFeeCoefficient {
+				coeff_integer: Balance::max_value(),
+				coeff_frac: Perbill::zero(),
+				degree: 2,
+			}];
+		}
+		assert_eq!(
+			PolynomialWeightToFee::<HugeCoefficients>::convert(Weight::max_value()),
+			Balance::max_value(),
+		);
+	}
+}
@@ -9,19 +9,31 @@
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
 use sp_std::prelude::*;
-use sp_core::OpaqueMetadata;
+use codec::{Encode, Decode};
+use sp_core::{OpaqueMetadata, u32_trait::{_1, _2, _3, _4}};
 use sp_runtime::{
-	ApplyExtrinsicResult, transaction_validity::TransactionValidity, generic, create_runtime_str,
-	impl_opaque_keys, MultiSignature,
+	ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature,
+	RuntimeDebug,
+	curve::PiecewiseLinear,
+	transaction_validity::TransactionValidity,
 };
 use sp_runtime::traits::{
-	BlakeTwo256, Block as BlockT, IdentityLookup, Verify, ConvertInto, IdentifyAccount
+	BlakeTwo256, Block as BlockT, ConvertInto, StaticLookup, Verify, IdentifyAccount, OpaqueKeys,
+	OnRuntimeUpgrade, OnInitialize, OnFinalize, OffchainWorker,
 };
 use sp_api::impl_runtime_apis;
+use frame_support::weights::WeighBlock;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use grandpa::AuthorityList as GrandpaAuthorityList;
 use grandpa::fg_primitives;
+use im_online::sr25519::AuthorityId as ImOnlineId;
+use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
 use sp_version::RuntimeVersion;
+use pallet_transaction_payment_rpc_runtime_api::RuntimeDispatchInfo;
+use contracts_rpc_runtime_api::ContractExecResult;
+use session::historical as session_historical;
+use system::offchain::TransactionSubmitter;
+use proxy::InstanceFilter;
 #[cfg(feature = "std")]
 use sp_version::NativeVersion;
 
@@ -30,16 +42,32 @@ use sp_version::NativeVersion;
 pub use sp_runtime::BuildStorage;
 pub use timestamp::Call as TimestampCall;
 pub use balances::Call as BalancesCall;
-pub use sp_runtime::{Permill, Perbill};
+pub use staking::StakerStatus;
+pub use contracts::Schedule as ContractsSchedule;
+pub use sp_runtime::{Permill, Perbill, Perquintill, Percent};
 pub use frame_support::{
 	StorageValue, construct_runtime, parameter_types,
-	traits::Randomness,
+	traits::{Currency, Randomness},
 	weights::Weight,
 };
 
 /// Importing a template pallet
 pub use template;
 
+/// Importing the validator set pallet
+pub use validator_set;
+
+/// Implementations of some helper traits passed into runtime modules as associated types.
+pub mod impls;
+
+/// The EVM compatibility layer.
+#[cfg(feature = "evm")]
+pub mod evm;
+use impls::{
+	CurrencyToVoteHandler, DealWithFees, ForceNewEra, PolynomialWeightToFee,
+	TargetedFeeAdjustment, WeightToFeeCoefficient,
+};
+
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -86,6 +114,8 @@ pub mod opaque {
 		pub struct SessionKeys {
 			pub aura: Aura,
 			pub grandpa: Grandpa,
+			pub im_online: ImOnline,
+			pub authority_discovery: AuthorityDiscovery,
 		}
 	}
 }
@@ -95,7 +125,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("node-template"),
 	impl_name: create_runtime_str!("node-template"),
 	authoring_version: 1,
-	spec_version: 1,
+	spec_version: 2,
 	impl_version: 1,
 	apis: RUNTIME_API_VERSIONS,
 };
@@ -109,6 +139,14 @@ pub const MINUTES: BlockNumber = 60_000 / (MILLISECS_PER_BLOCK as BlockNumber);
 pub const HOURS: BlockNumber = MINUTES * 60;
 pub const DAYS: BlockNumber = HOURS * 24;
 
+// These currency units are defined in number of the smallest balance unit.
+pub const MILLICENTS: Balance = 1_000_000_000;
+pub const CENTS: Balance = 1_000 * MILLICENTS;
+pub const DOLLARS: Balance = 100 * CENTS;
+
+/// The length of a session, after which the validators of the next session take over.
+pub const SESSION_PERIOD: BlockNumber = 10 * MINUTES;
+
 /// The version information used to identify this runtime when compiled natively.
 #[cfg(feature = "std")]
 pub fn native_version() -> NativeVersion {
@@ -131,8 +169,9 @@ impl system::Trait for Runtime {
 	type AccountId = AccountId;
 	/// The aggregated dispatch type that is available for extrinsics.
 	type Call = Call;
-	/// The lookup mechanism to get account ID from whatever is passed in dispatchers.
-	type Lookup = IdentityLookup<AccountId>;
+	/// The lookup mechanism to get account ID from whatever is passed in dispatchers: either the
+	/// account ID itself or its index.
+	type Lookup = Indices;
 	/// The index type for storing how many extrinsics an account has signed.
 	type Index = Index;
 	/// The index type for blocks.
@@ -188,6 +227,19 @@ impl timestamp::Trait for Runtime {
 	type MinimumPeriod = MinimumPeriod;
 }
 
+parameter_types! {
+	/// The deposit reserved for claiming an account index.
+	pub const IndexDeposit: Balance = 1 * DOLLARS;
+}
+
+impl indices::Trait for Runtime {
+	/// The type for recording indexing into the account enumeration.
+	type AccountIndex = AccountIndex;
+	type Currency = Balances;
+	type Deposit = IndexDeposit;
+	type Event = Event;
+}
+
 parameter_types! {
 	pub const ExistentialDeposit: u128 = 500;
 }
@@ -197,23 +249,517 @@ impl balances::Trait for Runtime {
 	type Balance = Balance;
 	/// The ubiquitous event type.
 	type Event = Event;
-	type DustRemoval = ();
+	/// Dust of reaped accounts goes to the treasury.
+	type DustRemoval = Treasury;
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = System;
 }
 
 parameter_types! {
-	pub const TransactionBaseFee: Balance = 0;
+	pub const MinVestedTransfer: Balance = 100 * DOLLARS;
+}
+
+impl vesting::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type BlockNumberToBalance = ConvertInto;
+	/// The smallest amount that can be transferred with `vested_transfer`.
+	type MinVestedTransfer = MinVestedTransfer;
+}
+
+pub type NegativeImbalance = <Balances as Currency<AccountId>>::NegativeImbalance;
+
+parameter_types! {
+	pub const TransactionBaseFee: Balance = 1_000_000;
 	pub const TransactionByteFee: Balance = 1;
+	/// The fee of a transaction of weight `w` is `w + w^2 / 10^9`, so that transactions filling a
+	/// large part of a block pay more than proportionally.
+	pub WeightToFeeCoefficients: Vec<WeightToFeeCoefficient> = vec![
+		WeightToFeeCoefficient {
+			coeff_integer: 1,
+			coeff_frac: Perbill::zero(),
+			degree: 1,
+		},
+		WeightToFeeCoefficient {
+			coeff_integer: 0,
+			coeff_frac: Perbill::from_parts(1),
+			degree: 2,
+		},
+	];
+	/// The portion of the weight available to normal transactions that blocks should be filled to.
+	/// Fees rise while blocks are fuller than this and fall while they are emptier.
+	pub const TargetBlockFullness: Perquintill = Perquintill::from_percent(25);
 }
 
 impl transaction_payment::Trait for Runtime {
-	type Currency = balances::Module<Runtime>;
-	type OnTransactionPayment = ();
+	type Currency = Balances;
+	/// 80% of the fees go to the treasury, 20% to the block author.
+	type OnTransactionPayment = DealWithFees<Treasury>;
 	type TransactionBaseFee = TransactionBaseFee;
 	type TransactionByteFee = TransactionByteFee;
-	type WeightToFee = ConvertInto;
-	type FeeMultiplierUpdate = ();
+	type WeightToFee = PolynomialWeightToFee<WeightToFeeCoefficients>;
+	type FeeMultiplierUpdate = TargetedFeeAdjustment<TargetBlockFullness>;
+}
+
+parameter_types! {
+	pub const UncleGenerations: BlockNumber = 0;
+}
+
+impl authorship::Trait for Runtime {
+	type FindAuthor = session::FindAccountFromAuthorIndex<Self, Aura>;
+	type UncleGenerations = UncleGenerations;
+	type FilterUncle = ();
+	type EventHandler = (Staking, ImOnline);
+}
+
+parameter_types! {
+	pub const SessionPeriod: BlockNumber = SESSION_PERIOD;
+	pub const SessionOffset: BlockNumber = 0;
+	pub const DisabledValidatorsThreshold: Perbill = Perbill::from_percent(17);
+}
+
+impl session::Trait for Runtime {
+	type Event = Event;
+	/// Validators are identified by their stash account.
+	type ValidatorId = <Self as system::Trait>::AccountId;
+	type ValidatorIdOf = staking::StashOf<Self>;
+	/// Sessions rotate every `SessionPeriod` blocks.
+	type ShouldEndSession = session::PeriodicSessions<SessionPeriod, SessionOffset>;
+	/// The validators of each session are elected by the staking pallet, then changed by root
+	/// through the validator set pallet.
+	type SessionManager = session_historical::NoteHistoricalRoot<
+		Self,
+		validator_set::WithChanges<Self, Staking>,
+	>;
+	type SessionHandler = <opaque::SessionKeys as OpaqueKeys>::KeyTypeIdProviders;
+	type Keys = opaque::SessionKeys;
+	type DisabledValidatorsThreshold = DisabledValidatorsThreshold;
+}
+
+impl session_historical::Trait for Runtime {
+	type FullIdentification = staking::Exposure<AccountId, Balance>;
+	type FullIdentificationOf = staking::ExposureOf<Runtime>;
+}
+
+pallet_staking_reward_curve::build! {
+	const REWARD_CURVE: PiecewiseLinear<'static> = curve!(
+		min_inflation: 0_025_000,
+		max_inflation: 0_100_000,
+		ideal_stake: 0_500_000,
+		falloff: 0_050_000,
+		max_piece_count: 40,
+		test_precision: 0_005_000,
+	);
+}
+
+parameter_types! {
+	/// The number of sessions in an era, after which a new set of validators is elected.
+	pub const SessionsPerEra: sp_staking::SessionIndex = 6;
+	/// The number of eras that funds remain bonded after unbonding.
+	pub const BondingDuration: staking::EraIndex = 24 * 28;
+	/// The number of eras that slashes are deferred by, during which they can be cancelled.
+	pub const SlashDeferDuration: staking::EraIndex = 24 * 7;
+	pub const RewardCurve: &'static PiecewiseLinear<'static> = &REWARD_CURVE;
+	pub const MaxNominatorRewardedPerValidator: u32 = 64;
+}
+
+impl staking::Trait for Runtime {
+	type Currency = Balances;
+	type Time = Timestamp;
+	type CurrencyToVote = CurrencyToVoteHandler;
+	/// Inflation not paid out as rewards goes to the treasury.
+	type RewardRemainder = Treasury;
+	type Event = Event;
+	/// Slashed funds go to the treasury.
+	type Slash = Treasury;
+	/// Rewards are minted from the void.
+	type Reward = ();
+	type SessionsPerEra = SessionsPerEra;
+	type BondingDuration = BondingDuration;
+	type SlashDeferDuration = SlashDeferDuration;
+	/// Deferred slashes can be cancelled by root or a 2/3 majority of the council.
+	type SlashCancelOrigin = EnsureRootOrTwoThirdsCouncil;
+	type SessionInterface = Self;
+	type RewardCurve = RewardCurve;
+	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
+}
+
+impl validator_set::Trait for Runtime {
+	type Event = Event;
+	/// Root's changes take effect with the validators of the next era, which is forced to start
+	/// at the next session rotation.
+	type OnChange = ForceNewEra;
+}
+
+impl offences::Trait for Runtime {
+	type Event = Event;
+	type IdentificationTuple = session_historical::IdentificationTuple<Self>;
+	type OnOffenceHandler = Staking;
+}
+
+parameter_types! {
+	/// The expected length of a session, for spreading heartbeats over it.
+	pub const SessionDuration: BlockNumber = SESSION_PERIOD;
+}
+
+impl im_online::Trait for Runtime {
+	type AuthorityId = ImOnlineId;
+	type Event = Event;
+	type Call = Call;
+	/// Heartbeats are sent as unsigned transactions from the offchain worker.
+	type SubmitTransaction = TransactionSubmitter<ImOnlineId, Runtime, UncheckedExtrinsic>;
+	type SessionDuration = SessionDuration;
+	/// Validators that sent no heartbeat and authored no block in a session are reported as offline.
+	type ReportUnresponsiveness = Offences;
+}
+
+impl authority_discovery::Trait for Runtime {}
+
+/// Root, or more than half of the council.
+type EnsureRootOrHalfCouncil = system::EnsureOneOf<
+	AccountId,
+	system::EnsureRoot<AccountId>,
+	collective::EnsureProportionMoreThan<_1, _2, AccountId, CouncilCollective>,
+>;
+
+/// Root, or at least two thirds of the council.
+type EnsureRootOrTwoThirdsCouncil = system::EnsureOneOf<
+	AccountId,
+	system::EnsureRoot<AccountId>,
+	collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>,
+>;
+
+parameter_types! {
+	pub const LaunchPeriod: BlockNumber = 7 * DAYS;
+	pub const VotingPeriod: BlockNumber = 7 * DAYS;
+	/// The shortest voting period the technical committee can fast-track a referendum to.
+	pub const FastTrackVotingPeriod: BlockNumber = 3 * HOURS;
+	pub const InstantAllowed: bool = true;
+	pub const MinimumDeposit: Balance = 100 * DOLLARS;
+	/// The delay between a referendum passing and its proposal being enacted.
+	pub const EnactmentPeriod: BlockNumber = 8 * DAYS;
+	pub const CooloffPeriod: BlockNumber = 7 * DAYS;
+	pub const PreimageByteDeposit: Balance = 1 * CENTS;
+}
+
+impl democracy::Trait for Runtime {
+	type Proposal = Call;
+	type Event = Event;
+	type Currency = Balances;
+	type EnactmentPeriod = EnactmentPeriod;
+	type LaunchPeriod = LaunchPeriod;
+	type VotingPeriod = VotingPeriod;
+	type MinimumDeposit = MinimumDeposit;
+	/// A straight majority of the council can decide what their next motion is.
+	type ExternalOrigin = collective::EnsureProportionAtLeast<_1, _2, AccountId, CouncilCollective>;
+	/// A 3/4 majority of the council can have the next scheduled referendum be a straight
+	/// majority-carries vote.
+	type ExternalMajorityOrigin = collective::EnsureProportionAtLeast<_3, _4, AccountId, CouncilCollective>;
+	/// A unanimous council can have the next scheduled referendum be a straight default-carries
+	/// (negative turnout biased) vote.
+	type ExternalDefaultOrigin = collective::EnsureProportionAtLeast<_1, _1, AccountId, CouncilCollective>;
+	/// Two thirds of the technical committee can have an external referendum voted on sooner.
+	type FastTrackOrigin = collective::EnsureProportionAtLeast<_2, _3, AccountId, TechnicalCollective>;
+	/// A unanimous technical committee can have an external referendum voted on immediately.
+	type InstantOrigin = collective::EnsureProportionAtLeast<_1, _1, AccountId, TechnicalCollective>;
+	type InstantAllowed = InstantAllowed;
+	type FastTrackVotingPeriod = FastTrackVotingPeriod;
+	/// Two thirds of the council can cancel a passed referendum before it is enacted.
+	type CancellationOrigin = EnsureRootOrTwoThirdsCouncil;
+	/// Any single technical committee member may veto a coming council proposal, however they
+	/// can only do it once and it lasts only for the cooloff period.
+	type VetoOrigin = collective::EnsureMember<AccountId, TechnicalCollective>;
+	type CooloffPeriod = CooloffPeriod;
+	type PreimageByteDeposit = PreimageByteDeposit;
+	type Slash = Treasury;
+}
+
+parameter_types! {
+	pub const CouncilMotionDuration: BlockNumber = 5 * DAYS;
+}
+
+type CouncilCollective = collective::Instance1;
+impl collective::Trait<CouncilCollective> for Runtime {
+	type Origin = Origin;
+	type Proposal = Call;
+	type Event = Event;
+	type MotionDuration = CouncilMotionDuration;
+}
+
+parameter_types! {
+	pub const CandidacyBond: Balance = 10 * DOLLARS;
+	pub const VotingBond: Balance = 1 * DOLLARS;
+	/// The council is re-elected once every term.
+	pub const TermDuration: BlockNumber = 7 * DAYS;
+	pub const DesiredMembers: u32 = 13;
+	pub const DesiredRunnersUp: u32 = 7;
+}
+
+impl elections_phragmen::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	/// The elected members make up the council.
+	type ChangeMembers = Council;
+	type CurrencyToVote = CurrencyToVoteHandler;
+	type CandidacyBond = CandidacyBond;
+	type VotingBond = VotingBond;
+	type LoserCandidate = Treasury;
+	type BadReport = Treasury;
+	type KickedMember = Treasury;
+	type DesiredMembers = DesiredMembers;
+	type DesiredRunnersUp = DesiredRunnersUp;
+	type TermDuration = TermDuration;
+}
+
+parameter_types! {
+	pub const TechnicalMotionDuration: BlockNumber = 5 * DAYS;
+}
+
+type TechnicalCollective = collective::Instance2;
+impl collective::Trait<TechnicalCollective> for Runtime {
+	type Origin = Origin;
+	type Proposal = Call;
+	type Event = Event;
+	type MotionDuration = TechnicalMotionDuration;
+}
+
+/// The members of the technical committee are managed by the council.
+impl membership::Trait<membership::Instance1> for Runtime {
+	type Event = Event;
+	type AddOrigin = EnsureRootOrHalfCouncil;
+	type RemoveOrigin = EnsureRootOrHalfCouncil;
+	type SwapOrigin = EnsureRootOrHalfCouncil;
+	type ResetOrigin = EnsureRootOrHalfCouncil;
+	type PrimeOrigin = EnsureRootOrHalfCouncil;
+	type MembershipInitialized = TechnicalCommittee;
+	type MembershipChanged = TechnicalCommittee;
+}
+
+parameter_types! {
+	pub const ProposalBond: Permill = Permill::from_percent(5);
+	pub const ProposalBondMinimum: Balance = 1 * DOLLARS;
+	/// Approved proposals are paid out, and the remaining funds partly burned, once every budget period.
+	pub const SpendPeriod: BlockNumber = 1 * DAYS;
+	pub const Burn: Permill = Permill::from_percent(50);
+	pub const TipCountdown: BlockNumber = 1 * DAYS;
+	pub const TipFindersFee: Percent = Percent::from_percent(20);
+	pub const TipReportDepositBase: Balance = 1 * DOLLARS;
+	pub const TipReportDepositPerByte: Balance = 1 * CENTS;
+}
+
+impl treasury::Trait for Runtime {
+	type Currency = Balances;
+	/// Spending proposals are approved by two thirds and rejected by half of the council.
+	type ApproveOrigin = EnsureRootOrTwoThirdsCouncil;
+	type RejectOrigin = EnsureRootOrHalfCouncil;
+	/// Tips are given by the elected council members.
+	type Tippers = Elections;
+	type TipCountdown = TipCountdown;
+	type TipFindersFee = TipFindersFee;
+	type TipReportDepositBase = TipReportDepositBase;
+	type TipReportDepositPerByte = TipReportDepositPerByte;
+	type Event = Event;
+	type ProposalRejection = Treasury;
+	type ProposalBond = ProposalBond;
+	type ProposalBondMinimum = ProposalBondMinimum;
+	type SpendPeriod = SpendPeriod;
+	type Burn = Burn;
+}
+
+parameter_types! {
+	pub const ContractTransactionBaseFee: Balance = 1 * CENTS;
+	pub const ContractTransactionByteFee: Balance = 10 * MILLICENTS;
+	pub const ContractFee: Balance = 1 * CENTS;
+	pub const TombstoneDeposit: Balance = 1 * DOLLARS;
+	pub const RentByteFee: Balance = 1 * DOLLARS;
+	pub const RentDepositOffset: Balance = 1000 * DOLLARS;
+	pub const SurchargeReward: Balance = 150 * DOLLARS;
+}
+
+impl contracts::Trait for Runtime {
+	type Currency = Balances;
+	type Time = Timestamp;
+	type Randomness = RandomnessCollectiveFlip;
+	type Call = Call;
+	type Event = Event;
+	type DetermineContractAddress = contracts::SimpleAddressDeterminer<Runtime>;
+	type ComputeDispatchFee = contracts::DefaultDispatchFeeComputor<Runtime>;
+	type TrieIdGenerator = contracts::TrieIdFromParentCounter<Runtime>;
+	/// Gas is bought at the `gas_price` of the genesis config, and the fees go to the treasury.
+	type GasPayment = Treasury;
+	type RentPayment = Treasury;
+	type SignedClaimHandicap = contracts::DefaultSignedClaimHandicap;
+	type TombstoneDeposit = TombstoneDeposit;
+	type StorageSizeOffset = contracts::DefaultStorageSizeOffset;
+	type RentByteFee = RentByteFee;
+	type RentDepositOffset = RentDepositOffset;
+	type SurchargeReward = SurchargeReward;
+	type TransactionBaseFee = ContractTransactionBaseFee;
+	type TransactionByteFee = ContractTransactionByteFee;
+	type ContractFee = ContractFee;
+	type CallBaseFee = contracts::DefaultCallBaseFee;
+	type InstantiateBaseFee = contracts::DefaultInstantiateBaseFee;
+	type MaxDepth = contracts::DefaultMaxDepth;
+	type MaxValueSize = contracts::DefaultMaxValueSize;
+	type BlockGasLimit = contracts::DefaultBlockGasLimit;
+}
+
+parameter_types! {
+	/// The deposit reserved for an identity, plus `FieldDeposit` per additional field.
+	pub const BasicDeposit: Balance = 10 * DOLLARS;
+	pub const FieldDeposit: Balance = 250 * CENTS;
+	pub const SubAccountDeposit: Balance = 2 * DOLLARS;
+	pub const MaxSubAccounts: u32 = 100;
+	pub const MaxAdditionalFields: u32 = 100;
+}
+
+impl identity::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	/// Deposits of identities killed by `ForceOrigin` go to the treasury.
+	type Slashed = Treasury;
+	type BasicDeposit = BasicDeposit;
+	type FieldDeposit = FieldDeposit;
+	type SubAccountDeposit = SubAccountDeposit;
+	type MaxSubAccounts = MaxSubAccounts;
+	type MaxAdditionalFields = MaxAdditionalFields;
+	/// Registrars are added by root.
+	type RegistrarOrigin = system::EnsureRoot<AccountId>;
+	type ForceOrigin = EnsureRootOrHalfCouncil;
+}
+
+impl identity_registrars::Trait for Runtime {}
+
+parameter_types! {
+	/// The deposit reserved for a multisig operation, plus `MultisigDepositFactor` per signatory.
+	pub const MultisigDepositBase: Balance = 1 * DOLLARS;
+	pub const MultisigDepositFactor: Balance = 5 * CENTS;
+	pub const MaxSignatories: u16 = 100;
+}
+
+/// Batches of calls, derivative accounts and multisig operations.
+impl utility::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Currency = Balances;
+	type MultisigDepositBase = MultisigDepositBase;
+	type MultisigDepositFactor = MultisigDepositFactor;
+	type MaxSignatories = MaxSignatories;
+}
+
+/// The kinds of proxies an account can have.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, RuntimeDebug)]
+pub enum ProxyType {
+	/// May dispatch any call.
+	Any,
+	/// May dispatch any call that cannot move funds out of the account.
+	NonTransfer,
+	/// May only take part in governance.
+	Governance,
+}
+
+impl Default for ProxyType {
+	fn default() -> Self { ProxyType::Any }
+}
+
+impl InstanceFilter<Call> for ProxyType {
+	fn filter(&self, c: &Call) -> bool {
+		match self {
+			ProxyType::Any => true,
+			// Only calls known not to move funds are allowed, so that pallets added later are
+			// denied until reviewed. Calls nested in utility, proxy or recovery calls are not
+			// filtered, so these are denied too, as are balances, contracts and EVM calls. The
+			// indices pallet of this Substrate version has no `freeze` call.
+			ProxyType::NonTransfer => match c {
+				Call::System(..) | Call::RandomnessCollectiveFlip(..) | Call::Timestamp(..) |
+				Call::Authorship(..) |
+				Call::Indices(indices::Call::claim(..)) | Call::Indices(indices::Call::free(..)) |
+				Call::Vesting(vesting::Call::vest(..)) |
+				Call::Vesting(vesting::Call::vest_other(..)) |
+				Call::Treasury(..) | Call::Staking(..) | Call::Session(..) | Call::Offences(..) |
+				Call::Grandpa(..) | Call::ImOnline(..) | Call::AuthorityDiscovery(..) |
+				Call::Democracy(..) | Call::Council(..) | Call::TechnicalCommittee(..) |
+				Call::Elections(..) | Call::TechnicalMembership(..) | Call::Identity(..) |
+				Call::TemplateModule(..) => true,
+				_ => false,
+			},
+			ProxyType::Governance => match c {
+				Call::Democracy(..) | Call::Council(..) | Call::TechnicalCommittee(..) |
+				Call::Elections(..) | Call::Treasury(..) => true,
+				_ => false,
+			},
+		}
+	}
+}
+
+parameter_types! {
+	pub const ProxyDepositBase: Balance = 1 * DOLLARS;
+	pub const ProxyDepositFactor: Balance = 10 * CENTS;
+	pub const MaxProxies: u16 = 32;
+}
+
+impl proxy::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Currency = Balances;
+	type ProxyType = ProxyType;
+	type ProxyDepositBase = ProxyDepositBase;
+	type ProxyDepositFactor = ProxyDepositFactor;
+	type MaxProxies = MaxProxies;
+}
+
+parameter_types! {
+	/// The deposit reserved for making an account recoverable, plus `FriendDepositFactor` per
+	/// friend.
+	pub const ConfigDepositBase: Balance = 5 * DOLLARS;
+	pub const FriendDepositFactor: Balance = 50 * CENTS;
+	pub const MaxFriends: u16 = 9;
+	/// The deposit reserved by a rescuer for initiating the recovery of an account.
+	pub const RecoveryDeposit: Balance = 5 * DOLLARS;
+}
+
+impl recovery::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type Currency = Balances;
+	type ConfigDepositBase = ConfigDepositBase;
+	type FriendDepositFactor = FriendDepositFactor;
+	type MaxFriends = MaxFriends;
+	type RecoveryDeposit = RecoveryDeposit;
+}
+
+parameter_types! {
+	/// Scheduled calls may use up to 80% of a block, apart from those with a hard deadline.
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * MaximumBlockWeight::get();
+}
+
+impl scheduler::Trait for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+}
+
+#[cfg(feature = "evm")]
+impl pallet_evm::Trait for Runtime {
+	type FeeCalculator = evm::FixedGasPrice;
+	/// The EVM address of an account is the first 20 bytes of the hash of its account ID.
+	type ConvertAccountId = pallet_evm::HashTruncateConvertAccountId<BlakeTwo256>;
+	type Currency = Balances;
+	type Event = Event;
+	type Precompiles = ();
+}
+
+#[cfg(feature = "evm")]
+parameter_types! {
+	/// The EIP-155 chain ID Ethereum transactions must be signed for, the one Ethereum tools use
+	/// for local development networks. A public chain needs an ID of its own.
+	pub const EthereumChainId: u64 = 1337;
+}
+
+#[cfg(feature = "evm")]
+impl pallet_ethereum::Trait for Runtime {
+	type Event = Event;
+	type ChainId = EthereumChainId;
 }
 
 impl sudo::Trait for Runtime {
@@ -224,8 +770,12 @@ impl sudo::Trait for Runtime {
 /// Used for the module template in `./template.rs`
 impl template::Trait for Runtime {
 	type Event = Event;
+	type WeightInfo = ();
 }
 
+// The runtime is declared twice, with and without the EVM pallet. Both declarations must list the
+// other pallets in the same order.
+#[cfg(not(feature = "evm"))]
 construct_runtime!(
 	pub enum Runtime where
 		Block = Block,
@@ -235,18 +785,92 @@ construct_runtime!(
 		System: system::{Module, Call, Config, Storage, Event<T>},
 		RandomnessCollectiveFlip: randomness_collective_flip::{Module, Call, Storage},
 		Timestamp: timestamp::{Module, Call, Storage, Inherent},
+		Authorship: authorship::{Module, Call, Storage, Inherent},
+		Indices: indices::{Module, Call, Storage, Config<T>, Event<T>},
+		Balances: balances::{Module, Call, Storage, Config<T>, Event<T>},
+		Vesting: vesting::{Module, Call, Storage, Event<T>, Config<T>},
+		TransactionPayment: transaction_payment::{Module, Storage},
+		Treasury: treasury::{Module, Call, Storage, Config, Event<T>},
+		// Staking must come before Session, so that the genesis stakers are
+		// known when the first session's validators are elected.
+		Staking: staking::{Module, Call, Config<T>, Storage, Event<T>},
+		ValidatorSet: validator_set::{Module, Call, Storage, Event<T>, Config<T>},
+		Session: session::{Module, Call, Storage, Event, Config<T>},
+		Historical: session_historical::{Module},
+		Offences: offences::{Module, Call, Storage, Event},
 		Aura: aura::{Module, Config<T>, Inherent(Timestamp)},
 		Grandpa: grandpa::{Module, Call, Storage, Config, Event},
+		ImOnline: im_online::{Module, Call, Storage, Event<T>, ValidateUnsigned, Config<T>},
+		AuthorityDiscovery: authority_discovery::{Module, Call, Config},
+		Democracy: democracy::{Module, Call, Storage, Config, Event<T>},
+		Council: collective::<Instance1>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>},
+		TechnicalCommittee: collective::<Instance2>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>},
+		Elections: elections_phragmen::{Module, Call, Storage, Event<T>},
+		TechnicalMembership: membership::<Instance1>::{Module, Call, Storage, Event<T>, Config<T>},
+		Contracts: contracts::{Module, Call, Config<T>, Storage, Event<T>},
+		Identity: identity::{Module, Call, Storage, Event<T>},
+		IdentityRegistrars: identity_registrars::{Module, Config<T>},
+		Utility: utility::{Module, Call, Storage, Event<T>},
+		Proxy: proxy::{Module, Call, Storage, Event<T>},
+		Recovery: recovery::{Module, Call, Storage, Event<T>},
+		Scheduler: scheduler::{Module, Call, Storage, Event<T>},
+		Sudo: sudo::{Module, Call, Config<T>, Storage, Event<T>},
+		// Used for the module template in `./template.rs`
+		TemplateModule: template::{Module, Call, Storage, Event<T>, Config<T>},
+	}
+);
+
+// The EVM comes last, so that the indices of the other pallets, and with them the encoding of
+// their calls and events, do not depend on the `evm` feature.
+#[cfg(feature = "evm")]
+construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = opaque::Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: system::{Module, Call, Config, Storage, Event<T>},
+		RandomnessCollectiveFlip: randomness_collective_flip::{Module, Call, Storage},
+		Timestamp: timestamp::{Module, Call, Storage, Inherent},
+		Authorship: authorship::{Module, Call, Storage, Inherent},
+		Indices: indices::{Module, Call, Storage, Config<T>, Event<T>},
 		Balances: balances::{Module, Call, Storage, Config<T>, Event<T>},
+		Vesting: vesting::{Module, Call, Storage, Event<T>, Config<T>},
 		TransactionPayment: transaction_payment::{Module, Storage},
+		Treasury: treasury::{Module, Call, Storage, Config, Event<T>},
+		// Staking must come before Session, so that the genesis stakers are
+		// known when the first session's validators are elected.
+		Staking: staking::{Module, Call, Config<T>, Storage, Event<T>},
+		ValidatorSet: validator_set::{Module, Call, Storage, Event<T>, Config<T>},
+		Session: session::{Module, Call, Storage, Event, Config<T>},
+		Historical: session_historical::{Module},
+		Offences: offences::{Module, Call, Storage, Event},
+		Aura: aura::{Module, Config<T>, Inherent(Timestamp)},
+		Grandpa: grandpa::{Module, Call, Storage, Config, Event},
+		ImOnline: im_online::{Module, Call, Storage, Event<T>, ValidateUnsigned, Config<T>},
+		AuthorityDiscovery: authority_discovery::{Module, Call, Config},
+		Democracy: democracy::{Module, Call, Storage, Config, Event<T>},
+		Council: collective::<Instance1>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>},
+		TechnicalCommittee: collective::<Instance2>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>},
+		Elections: elections_phragmen::{Module, Call, Storage, Event<T>},
+		TechnicalMembership: membership::<Instance1>::{Module, Call, Storage, Event<T>, Config<T>},
+		Contracts: contracts::{Module, Call, Config<T>, Storage, Event<T>},
+		Identity: identity::{Module, Call, Storage, Event<T>},
+		IdentityRegistrars: identity_registrars::{Module, Config<T>},
+		Utility: utility::{Module, Call, Storage, Event<T>},
+		Proxy: proxy::{Module, Call, Storage, Event<T>},
+		Recovery: recovery::{Module, Call, Storage, Event<T>},
+		Scheduler: scheduler::{Module, Call, Storage, Event<T>},
 		Sudo: sudo::{Module, Call, Config<T>, Storage, Event<T>},
 		// Used for the module template in `./template.rs`
-		TemplateModule: template::{Module, Call, Storage, Event<T>},
+		TemplateModule: template::{Module, Call, Storage, Event<T>, Config<T>},
+		EVM: pallet_evm::{Module, Call, Storage, Event},
+		Ethereum: pallet_ethereum::{Module, Call, Event, ValidateUnsigned},
 	}
 );
 
-/// The address format for describing accounts.
-pub type Address = AccountId;
+/// The address format for describing accounts: an account ID or an account index.
+pub type Address = <Indices as StaticLookup>::Source;
 /// Block header type as expected by this runtime.
 pub type Header = generic::Header<BlockNumber, BlakeTwo256>;
 /// Block type as expected by this runtime.
@@ -269,7 +893,79 @@ pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signatu
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExtra>;
 /// Executive: handles dispatch to the various modules.
-pub type Executive = frame_executive::Executive<Runtime, Block, system::ChainContext<Runtime>, Runtime, AllModules>;
+pub type Executive = frame_executive::Executive<
+	Runtime,
+	Block,
+	system::ChainContext<Runtime>,
+	Runtime,
+	AllModulesWithMigrations,
+>;
+
+/// Custom migrations run when the runtime is upgraded, before those of the pallets.
+pub type Migrations = (TemplateValueToSudoKey,);
+
+/// The hooks of all modules, running `Migrations` first on a runtime upgrade.
+pub struct AllModulesWithMigrations;
+
+impl OnRuntimeUpgrade for AllModulesWithMigrations {
+	fn on_runtime_upgrade() {
+		<Migrations as OnRuntimeUpgrade>::on_runtime_upgrade();
+		<AllModules as OnRuntimeUpgrade>::on_runtime_upgrade();
+	}
+}
+
+impl OnInitialize<BlockNumber> for AllModulesWithMigrations {
+	fn on_initialize(n: BlockNumber) {
+		<AllModules as OnInitialize<BlockNumber>>::on_initialize(n)
+	}
+}
+
+impl OnFinalize<BlockNumber> for AllModulesWithMigrations {
+	fn on_finalize(n: BlockNumber) {
+		<AllModules as OnFinalize<BlockNumber>>::on_finalize(n)
+	}
+}
+
+impl OffchainWorker<BlockNumber> for AllModulesWithMigrations {
+	fn offchain_worker(n: BlockNumber) {
+		<AllModules as OffchainWorker<BlockNumber>>::offchain_worker(n)
+	}
+}
+
+impl WeighBlock<BlockNumber> for AllModulesWithMigrations {
+	fn on_runtime_upgrade() -> Weight {
+		<AllModules as WeighBlock<BlockNumber>>::on_runtime_upgrade()
+	}
+
+	fn on_initialize(n: BlockNumber) -> Weight {
+		<AllModules as WeighBlock<BlockNumber>>::on_initialize(n)
+	}
+
+	fn on_finalize(n: BlockNumber) -> Weight {
+		<AllModules as WeighBlock<BlockNumber>>::on_finalize(n)
+	}
+}
+
+/// Gives the single `Something` value of the template pallet, from before it was stored per
+/// account, to the sudo key instead of letting the pallet's own migration drop it.
+pub struct TemplateValueToSudoKey;
+
+impl OnRuntimeUpgrade for TemplateValueToSudoKey {
+	fn on_runtime_upgrade() {
+		template::migration::migrate_to_per_account::<Runtime>(Some(&Sudo::key()));
+	}
+}
+
+#[cfg(feature = "try-runtime")]
+sp_api::decl_runtime_apis! {
+	/// Dry runs of runtime upgrades, for the node's `try-runtime` subcommand.
+	pub trait TryRuntime {
+		/// Run the migrations of a runtime upgrade as `Executive` does, checking the storage
+		/// before and after. Returns the weight the migrations registered with the system
+		/// pallet, which is capped to the maximum block weight, or the first failed check.
+		fn on_runtime_upgrade() -> Result<Weight, sp_runtime::RuntimeString>;
+	}
+}
 
 impl_runtime_apis! {
 	impl sp_api::Core<Block> for Runtime {
@@ -339,6 +1035,12 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl sp_authority_discovery::AuthorityDiscoveryApi<Block> for Runtime {
+		fn authorities() -> Vec<AuthorityDiscoveryId> {
+			AuthorityDiscovery::authorities()
+		}
+	}
+
 	impl sp_session::SessionKeys<Block> for Runtime {
 		fn generate_session_keys(seed: Option<Vec<u8>>) -> Vec<u8> {
 			opaque::SessionKeys::generate(seed)
@@ -356,4 +1058,405 @@ impl_runtime_apis! {
 			Grandpa::grandpa_authorities()
 		}
 	}
+
+	impl frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Index> for Runtime {
+		fn account_nonce(account: AccountId) -> Index {
+			System::account_nonce(account)
+		}
+	}
+
+	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance, UncheckedExtrinsic> for Runtime {
+		fn query_info(uxt: UncheckedExtrinsic, len: u32) -> RuntimeDispatchInfo<Balance> {
+			TransactionPayment::query_info(uxt, len)
+		}
+	}
+
+	impl contracts_rpc_runtime_api::ContractsApi<Block, AccountId, Balance, BlockNumber>
+		for Runtime
+	{
+		fn call(
+			origin: AccountId,
+			dest: AccountId,
+			value: Balance,
+			gas_limit: u64,
+			input_data: Vec<u8>,
+		) -> ContractExecResult {
+			match Contracts::bare_call(origin, dest, value, gas_limit, input_data) {
+				Ok(v) => ContractExecResult::Success {
+					status: v.status,
+					data: v.data,
+				},
+				Err(_) => ContractExecResult::Error,
+			}
+		}
+
+		fn get_storage(
+			address: AccountId,
+			key: [u8; 32],
+		) -> contracts_primitives::GetStorageResult {
+			Contracts::get_storage(address, key)
+		}
+
+		fn rent_projection(
+			address: AccountId,
+		) -> contracts_primitives::RentProjectionResult<BlockNumber> {
+			Contracts::rent_projection(address)
+		}
+	}
+
+	#[cfg(feature = "evm")]
+	impl evm_rpc_runtime_api::EVMApi<Block, AccountId> for Runtime {
+		fn address_of(account: AccountId) -> sp_core::H160 {
+			evm::address_of(&account)
+		}
+
+		fn account_basic(address: sp_core::H160) -> evm_rpc_runtime_api::EvmAccount {
+			evm::account_basic(address)
+		}
+
+		fn call(
+			from: sp_core::H160,
+			to: sp_core::H160,
+			data: Vec<u8>,
+			value: sp_core::U256,
+			gas_limit: u32,
+		) -> evm_rpc_runtime_api::CallResult {
+			evm::call(from, to, data, value, gas_limit)
+		}
+
+		fn chain_id() -> u64 {
+			EthereumChainId::get()
+		}
+
+		fn transaction_extrinsic(
+			transaction: evm_rpc_runtime_api::Transaction,
+		) -> <Block as BlockT>::Extrinsic {
+			UncheckedExtrinsic::new_unsigned(pallet_ethereum::Call::transact(transaction).into())
+		}
+	}
+
+	impl template_runtime_api::TemplateApi<Block, AccountId> for Runtime {
+		fn get_value(who: AccountId) -> Option<u32> {
+			TemplateModule::something(who)
+		}
+	}
+
+	#[cfg(feature = "try-runtime")]
+	impl TryRuntime<Block> for Runtime {
+		fn on_runtime_upgrade() -> Result<Weight, sp_runtime::RuntimeString> {
+			let sudo_key = Sudo::key();
+			let old_value = template::migration::pre_migrate_to_per_account::<Runtime>()?;
+
+			let weight_before = System::all_extrinsics_weight();
+			<AllModulesWithMigrations as OnRuntimeUpgrade>::on_runtime_upgrade();
+			let weight = System::all_extrinsics_weight().saturating_sub(weight_before);
+
+			template::migration::post_migrate_to_per_account::<Runtime>(old_value, Some(&sudo_key))?;
+			Ok(weight)
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	impl frame_benchmarking::Benchmark<Block> for Runtime {
+		fn dispatch_benchmark(
+			module: Vec<u8>,
+			extrinsic: Vec<u8>,
+			lowest_range_values: Vec<u32>,
+			highest_range_values: Vec<u32>,
+			steps: Vec<u32>,
+			repeat: u32,
+		) -> Result<Vec<frame_benchmarking::BenchmarkResults>, sp_runtime::RuntimeString> {
+			use frame_benchmarking::Benchmarking;
+
+			let result = match module.as_slice() {
+				b"pallet-template" | b"template" => TemplateModule::run_benchmark(
+					extrinsic,
+					lowest_range_values,
+					highest_range_values,
+					steps,
+					repeat,
+				),
+				_ => Err("Benchmark not found for this pallet."),
+			};
+
+			result.map_err(|e| e.into())
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::{assert_noop, assert_ok};
+	use frame_support::storage::migration::{get_storage_value, put_storage_value};
+	use sp_core::Pair;
+	use sp_keyring::AccountKeyring;
+	use sp_runtime::generic::{Era, SignedPayload};
+	use sp_runtime::traits::Checkable;
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		indices::GenesisConfig::<Runtime> {
+			indices: vec![(0, AccountKeyring::Alice.to_account_id())],
+		}.assimilate_storage(&mut t).unwrap();
+		t.into()
+	}
+
+	fn signed_extra() -> SignedExtra {
+		(
+			system::CheckVersion::new(),
+			system::CheckGenesis::new(),
+			system::CheckEra::from(Era::Immortal),
+			system::CheckNonce::from(0),
+			system::CheckWeight::new(),
+			transaction_payment::ChargeTransactionPayment::from(0),
+		)
+	}
+
+	fn signed_by_alice(address: Address) -> UncheckedExtrinsic {
+		let call = Call::System(system::Call::remark(vec![]));
+		let payload = SignedPayload::new(call, signed_extra()).unwrap();
+		let signature = payload.using_encoded(|p| AccountKeyring::Alice.pair().sign(p));
+		let (call, extra, _) = payload.deconstruct();
+		UncheckedExtrinsic::new_signed(call, address, signature.into(), extra)
+	}
+
+	#[test]
+	fn addresses_resolve_by_id_and_index() {
+		new_test_ext().execute_with(|| {
+			let alice = AccountKeyring::Alice.to_account_id();
+			assert_eq!(Indices::lookup(Address::Id(alice.clone())), Ok(alice.clone()));
+			assert_eq!(Indices::lookup(Address::Index(0)), Ok(alice));
+			assert!(Indices::lookup(Address::Index(1)).is_err());
+		});
+	}
+
+	#[test]
+	fn extrinsics_can_be_signed_with_an_index() {
+		new_test_ext().execute_with(|| {
+			let context = system::ChainContext::<Runtime>::default();
+			let alice = AccountKeyring::Alice.to_account_id();
+
+			let checked = signed_by_alice(Address::Index(0)).check(&context).unwrap();
+			assert_eq!(checked.signed.map(|(who, _)| who), Some(alice.clone()));
+			let checked = signed_by_alice(Address::Id(alice.clone())).check(&context).unwrap();
+			assert_eq!(checked.signed.map(|(who, _)| who), Some(alice));
+
+			// an unclaimed index cannot be resolved
+			assert!(signed_by_alice(Address::Index(1)).check(&context).is_err());
+		});
+	}
+
+	fn new_recovery_test_ext() -> sp_io::TestExternalities {
+		let mut t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		balances::GenesisConfig::<Runtime> {
+			balances: vec![
+				(AccountKeyring::Alice.to_account_id(), 100 * DOLLARS),
+				(AccountKeyring::Ferdie.to_account_id(), 100 * DOLLARS),
+			],
+		}.assimilate_storage(&mut t).unwrap();
+		let mut ext = sp_io::TestExternalities::from(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	/// Bob, Charlie and Dave, sorted as `create_recovery` requires.
+	fn friends() -> Vec<AccountId> {
+		let mut friends = vec![
+			AccountKeyring::Bob.to_account_id(),
+			AccountKeyring::Charlie.to_account_id(),
+			AccountKeyring::Dave.to_account_id(),
+		];
+		friends.sort();
+		friends
+	}
+
+	#[test]
+	fn lost_accounts_can_be_recovered() {
+		new_recovery_test_ext().execute_with(|| {
+			let alice = AccountKeyring::Alice.to_account_id();
+			let ferdie = AccountKeyring::Ferdie.to_account_id();
+			let friends = friends();
+
+			// Alice's account becomes recoverable by two of its three friends, after 10 blocks.
+			assert_ok!(Recovery::create_recovery(Origin::signed(alice.clone()), friends.clone(), 2, 10));
+			let config_deposit = ConfigDepositBase::get() + 3 * FriendDepositFactor::get();
+			assert_eq!(Balances::reserved_balance(&alice), config_deposit);
+
+			// Ferdie, the new key of Alice's owner, starts recovering the account.
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(ferdie.clone()), alice.clone()));
+			assert_eq!(Balances::reserved_balance(&ferdie), RecoveryDeposit::get());
+			for friend in &friends[..2] {
+				assert_ok!(Recovery::vouch_recovery(
+					Origin::signed(friend.clone()),
+					alice.clone(),
+					ferdie.clone(),
+				));
+			}
+
+			// the account can only be claimed once the delay period has passed
+			assert_noop!(
+				Recovery::claim_recovery(Origin::signed(ferdie.clone()), alice.clone()),
+				recovery::Error::<Runtime>::DelayPeriod
+			);
+			System::set_block_number(11);
+			assert_ok!(Recovery::claim_recovery(Origin::signed(ferdie.clone()), alice.clone()));
+
+			// Ferdie now dispatches calls as Alice.
+			let as_alice = |call| Recovery::as_recovered(
+				Origin::signed(ferdie.clone()),
+				alice.clone(),
+				Box::new(call),
+			);
+			let transfer = balances::Call::transfer(Address::Id(ferdie.clone()), 50 * DOLLARS);
+			assert_ok!(as_alice(Call::Balances(transfer)));
+			assert_eq!(Balances::free_balance(&alice), 50 * DOLLARS - config_deposit);
+			assert_eq!(Balances::free_balance(&ferdie), 150 * DOLLARS - RecoveryDeposit::get());
+
+			// Closing the recovery as Alice moves Ferdie's deposit to Alice, whose own deposit is
+			// returned once the account is no longer recoverable.
+			assert_ok!(as_alice(Call::Recovery(recovery::Call::close_recovery(ferdie.clone()))));
+			assert!(Recovery::active_recovery(&alice, &ferdie).is_none());
+			assert_eq!(Balances::reserved_balance(&ferdie), 0);
+			assert_eq!(
+				Balances::free_balance(&alice),
+				50 * DOLLARS - config_deposit + RecoveryDeposit::get(),
+			);
+
+			assert_ok!(as_alice(Call::Recovery(recovery::Call::remove_recovery())));
+			assert!(Recovery::recovery_config(&alice).is_none());
+			assert_eq!(Balances::reserved_balance(&alice), 0);
+			assert_eq!(Balances::free_balance(&alice), 50 * DOLLARS + RecoveryDeposit::get());
+		});
+	}
+
+	#[test]
+	fn recovery_needs_enough_friends() {
+		new_recovery_test_ext().execute_with(|| {
+			let alice = AccountKeyring::Alice.to_account_id();
+			let ferdie = AccountKeyring::Ferdie.to_account_id();
+			let friends = friends();
+
+			assert_ok!(Recovery::create_recovery(Origin::signed(alice.clone()), friends.clone(), 2, 10));
+			assert_ok!(Recovery::initiate_recovery(Origin::signed(ferdie.clone()), alice.clone()));
+
+			// only friends can vouch, and only once
+			assert_noop!(
+				Recovery::vouch_recovery(Origin::signed(ferdie.clone()), alice.clone(), ferdie.clone()),
+				recovery::Error::<Runtime>::NotFriend
+			);
+			assert_ok!(Recovery::vouch_recovery(
+				Origin::signed(friends[0].clone()),
+				alice.clone(),
+				ferdie.clone(),
+			));
+			assert_noop!(
+				Recovery::vouch_recovery(
+					Origin::signed(friends[0].clone()),
+					alice.clone(),
+					ferdie.clone(),
+				),
+				recovery::Error::<Runtime>::AlreadyVouched
+			);
+
+			System::set_block_number(11);
+			assert_noop!(
+				Recovery::claim_recovery(Origin::signed(ferdie.clone()), alice.clone()),
+				recovery::Error::<Runtime>::Threshold
+			);
+
+			// without a claimed recovery, Ferdie cannot act as Alice
+			let remark = Call::System(system::Call::remark(vec![]));
+			assert_noop!(
+				Recovery::as_recovered(Origin::signed(ferdie.clone()), alice.clone(), Box::new(remark)),
+				recovery::Error::<Runtime>::NotAllowed
+			);
+
+			// the recovery is noticed and closed from Alice's account, taking Ferdie's deposit
+			assert_ok!(Recovery::close_recovery(Origin::signed(alice.clone()), ferdie.clone()));
+			assert_eq!(Balances::reserved_balance(&ferdie), 0);
+			assert_eq!(Balances::free_balance(&ferdie), 100 * DOLLARS - RecoveryDeposit::get());
+			assert_noop!(
+				Recovery::claim_recovery(Origin::signed(ferdie.clone()), alice.clone()),
+				recovery::Error::<Runtime>::NotStarted
+			);
+		});
+	}
+
+	#[test]
+	fn non_transfer_proxies_cannot_transfer() {
+		new_recovery_test_ext().execute_with(|| {
+			let alice = AccountKeyring::Alice.to_account_id();
+			let ferdie = AccountKeyring::Ferdie.to_account_id();
+			assert_ok!(Proxy::add_proxy(
+				Origin::signed(alice.clone()),
+				ferdie.clone(),
+				ProxyType::NonTransfer,
+			));
+			assert_ok!(Indices::claim(Origin::signed(alice.clone()), 0));
+			let as_alice = |call| Proxy::proxy(
+				Origin::signed(ferdie.clone()),
+				alice.clone(),
+				Box::new(call),
+			);
+
+			// calls that move funds or indices are rejected
+			let transfer = balances::Call::transfer(Address::Id(ferdie.clone()), DOLLARS);
+			assert_noop!(as_alice(Call::Balances(transfer)), proxy::Error::<Runtime>::Unproxyable);
+			assert_noop!(
+				as_alice(Call::Indices(indices::Call::transfer(ferdie.clone(), 0))),
+				proxy::Error::<Runtime>::Unproxyable
+			);
+
+			// other calls are dispatched as Alice
+			assert_ok!(as_alice(Call::Indices(indices::Call::free(0))));
+			assert!(Indices::lookup(Address::Index(0)).is_err());
+		});
+	}
+
+	/// Storage as left by spec version 1, whose template pallet had a single `Something` value,
+	/// holding `old_value` in it and Alice as the sudo key.
+	fn pre_upgrade_ext(old_value: Option<u32>) -> sp_io::TestExternalities {
+		let mut t = system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		sudo::GenesisConfig::<Runtime> {
+			key: AccountKeyring::Alice.to_account_id(),
+		}.assimilate_storage(&mut t).unwrap();
+		let mut ext = sp_io::TestExternalities::from(t);
+		ext.execute_with(|| if let Some(value) = old_value {
+			put_storage_value(b"TemplateModule", b"Something", &[], value);
+		});
+		ext
+	}
+
+	/// Run the migrations as `Executive` does on a runtime upgrade.
+	fn upgrade() {
+		<AllModulesWithMigrations as OnRuntimeUpgrade>::on_runtime_upgrade();
+	}
+
+	#[test]
+	fn upgrade_gives_the_template_value_to_the_sudo_key() {
+		pre_upgrade_ext(Some(42)).execute_with(|| {
+			let alice = AccountKeyring::Alice.to_account_id();
+			assert_eq!(TemplateModule::storage_version(), template::Releases::V1_0_0);
+
+			upgrade();
+			assert_eq!(TemplateModule::storage_version(), template::Releases::V2_0_0);
+			assert_eq!(get_storage_value::<u32>(b"TemplateModule", b"Something", &[]), None);
+			assert_eq!(TemplateModule::something(&alice), Some(42));
+
+			// upgrading again changes nothing
+			assert_ok!(TemplateModule::do_something(Origin::signed(alice.clone()), 7));
+			upgrade();
+			assert_eq!(TemplateModule::something(&alice), Some(7));
+		});
+	}
+
+	#[test]
+	fn upgrade_without_a_template_value_only_records_the_storage_version() {
+		pre_upgrade_ext(None).execute_with(|| {
+			upgrade();
+			assert_eq!(TemplateModule::storage_version(), template::Releases::V2_0_0);
+			assert_eq!(TemplateModule::something(&AccountKeyring::Alice.to_account_id()), None);
+		});
+	}
 }